@@ -0,0 +1,61 @@
+// Cycle counts of c*a for a ternary challenge c against the NTT routes of
+// opening::last. Run with `cargo bench --bench sparse_mul`.
+
+use std::{arch::x86_64::_rdtsc, hint::black_box};
+
+use irelzk_rs::{
+    crypto::transcript::{self, Transcript},
+    params::{N, SYMBYTES},
+    poly_arith::poly::Poly
+};
+
+const RUNS: usize = 10000;
+
+fn median(mut f: impl FnMut()) -> u64 {
+    let mut times: Vec<u64> = (0..RUNS).map(|_| {
+        let start = unsafe { _rdtsc() };
+        f();
+        unsafe { _rdtsc() - start }
+    }).collect();
+    times.sort_unstable();
+    times[RUNS/2]
+}
+
+fn main() {
+    let mut transcript = Transcript::new(b"bench");
+    let c = transcript::sample_challenge(&mut transcript.challenge_xof(b"c"))[0];
+    let mut a = Poly::new();
+    Poly::trinary(&mut a, &[1u8; SYMBYTES], 0);
+    let (mut chat, mut ahat) = (c, a);
+    chat.ntt();
+    ahat.ntt();
+    let mut r = Poly::new();
+
+    let sparse = median(|| {
+        Poly::mul_sparse_ternary(&mut r, black_box(&c), black_box(&a));
+        black_box(&r);
+    });
+    let pointwise = median(|| {
+        Poly::pointwise_montgomery_other(&mut r, black_box(&chat), black_box(&ahat));
+        black_box(&r);
+    });
+    let invntt = median(|| {
+        Poly::pointwise_montgomery_other(&mut r, black_box(&chat), black_box(&ahat));
+        r.inverse_ntt();
+        black_box(&r);
+    });
+    let roundtrip = median(|| {
+        let mut t = *black_box(&a);
+        t.ntt();
+        Poly::pointwise_montgomery_other(&mut r, black_box(&chat), &t);
+        r.inverse_ntt();
+        black_box(&r);
+    });
+
+    let weight = c.coeffs.iter().filter(|&&x| x != 0).count();
+    eprintln!("c*a for a challenge of weight {} out of {}, median cycles", weight, N);
+    eprintln!("{:<36} {:>8}", "Poly::mul_sparse_ternary", sparse);
+    eprintln!("{:<36} {:>8}", "pointwise (NTT operands)", pointwise);
+    eprintln!("{:<36} {:>8}", "pointwise + inverse_ntt", invntt);
+    eprintln!("{:<36} {:>8}", "ntt + pointwise + inverse_ntt", roundtrip);
+}
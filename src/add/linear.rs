@@ -2,9 +2,8 @@
 #![allow(unused_assignments)]
 
 use crate::{
-    comm::commitment::{Comm, CommKey, CommRnd}, 
-    crypto::aes256::Aes256Ctx, 
-    params::{M, N, R, SYMBYTES}, 
+    comm::commitment::{CommKey, CommRnd, ExpandedComm}, 
+    params::{M, N, R}, 
     poly_arith::{
         consts::MONTSQ, 
         poly::Poly, 
@@ -17,11 +16,9 @@ use super::product::poly_shift;
 pub fn proof (
     vprime: &mut [Poly; R],
        msg: &PolyVecM,
-     chash: &[u8; SYMBYTES],
+     gamma: &[Poly; R],
          g: &[PolyVecM; R]
 ) -> Poly {
-    let mut nonce = 0;
-    let mut state = Aes256Ctx::init(&chash, nonce);
     let mut h = Poly::new();
     let vtmp: [Poly; R] = std::array::from_fn(|j| {
         let mut tmp = Poly::new();
@@ -37,21 +34,17 @@ pub fn proof (
         vprime[j] = g[j].vec[M-1].clone();
     }
     for i in 0..R {
-        state.select(nonce);
-        nonce += 1;
-        let mut gamma   = Poly::new();
         let mut atgamma = Poly::new();
         let mut tmp     = Poly::new();
         let mut tmp1    = Poly::new();
         
-        Poly::uniform_preinit(&mut gamma, &mut state);
         for j in 0..(N-1) {
-            atgamma.coeffs[j] = 2*gamma.coeffs[j] - gamma.coeffs[j+1];
+            atgamma.coeffs[j] = 2*gamma[i].coeffs[j] - gamma[i].coeffs[j+1];
         }
-        atgamma.coeffs[N-1] = 2*gamma.coeffs[N-1];
+        atgamma.coeffs[N-1] = 2*gamma[i].coeffs[N-1];
 
         for j in 0..4 {
-            Poly::pointwise_montgomery_other(&mut tmp, &gamma, &vtmp[j]);
+            Poly::pointwise_montgomery_other(&mut tmp, &gamma[i], &vtmp[j]);
             Poly::pointwise_montgomery_other(&mut tmp1, &atgamma, &g[j].vec[3]);
             tmp.sub(&tmp1);
             tmp.trace65_ntt();
@@ -60,7 +53,7 @@ pub fn proof (
             vprime[j].add(&tmp);
         }
         
-        Poly::pointwise_montgomery_other(&mut tmp, &gamma, &mpr);
+        Poly::pointwise_montgomery_other(&mut tmp, &gamma[i], &mpr);
         Poly::pointwise_montgomery_other(&mut tmp1, &atgamma, &msg.vec[3]);
         tmp.sub(&tmp1);
         tmp.trace65_ntt();
@@ -80,26 +73,17 @@ pub fn proof (
 
 pub fn verify (
     vprime: &mut [Poly; R],
-     chash: &[u8; SYMBYTES],
+     gamma: &[Poly; R],
          h: &Poly,
          c: &[Poly; R],
          z: &[CommRnd; R],
-        tp: &Comm,
+        tp: &ExpandedComm,
        ckp: &CommKey
 ) -> bool {
     if h.coeffs[0] != 0 || h.coeffs[1] != 0 || h.coeffs[2] != 0 || h.coeffs[3] != 0 {
         return true;
     }
-    
-    let mut nonce = 0;
-    let mut state = Aes256Ctx::init(&chash, nonce);
-    let gamma: [Poly; R] = std::array::from_fn(|_| {
-        state.select(nonce);
-        nonce += 1;
-        let mut a = Poly::new();
-        Poly::uniform_preinit(&mut a, &mut state);
-        a
-    });
+
     let atgamma: [Poly; R] = std::array::from_fn(|i| {
         let mut a = Poly::new();
         for j in 0..(N-1) {
@@ -110,11 +94,11 @@ pub fn verify (
     });
     let mut bpr = ckp.bm[0].clone();
     bpr.add(&ckp.bm[1]);
-    bpr.sub(&ckp.bm[3]);
+    bpr.sub(&ckp.bm[2]);
 
     let mut tpr = tp.tm.vec[0].clone();
     tpr.add(&tp.tm.vec[1]);
-    tpr.sub(&tp.tm.vec[3]);
+    tpr.sub(&tp.tm.vec[2]);
 
     let mut hhat = h.clone();
     hhat.ntt();
@@ -144,7 +128,7 @@ pub fn verify (
         vprime[i].scale_montgomery(MONTSQ as i32);
 
         Poly::add_other(&mut tmp, &z[i].em.vec[0], &z[i].em.vec[1]);
-        tmp.sub(&z[i].em.vec[3]);
+        tmp.sub(&z[i].em.vec[2]);
         tmp.ntt();
         vtmp1.add(&tmp);
 
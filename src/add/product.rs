@@ -1,9 +1,8 @@
 #![allow(dead_code)]
 
 use crate::{
-    comm::commitment::{Comm, CommKey, CommRnd}, 
-    crypto::aes256::Aes256Ctx, 
-    params::{M, N, R, SYMBYTES}, 
+    comm::commitment::{CommKey, CommRnd, ExpandedComm}, 
+    params::{M, N, R}, 
     poly_arith::{
         consts::{MONTSQ, NTTX, NTTX2, NTTX3, NTTX64}, 
         poly::Poly, 
@@ -12,7 +11,7 @@ use crate::{
 };
 
 #[inline(always)]
-fn poly_sigmainv_ntt(r: &mut Poly, a: &Poly, i: usize) {
+pub fn poly_sigmainv_ntt(r: &mut Poly, a: &Poly, i: usize) {
     if i == 0 {
         *r = *a;
     } else if i == 1 {
@@ -37,7 +36,7 @@ pub fn poly_shift(r: &mut Poly, a: &Poly, i: usize) {
     }
 }
 
-fn autobase_proof (r: &mut [Poly; R], a: &[PolyVecM; R], idx: usize) {
+pub fn autobase_proof (r: &mut [Poly; R], a: &[PolyVecM; R], idx: usize) {
     let mut b: [Poly; R] = std::array::from_fn(|_| Poly::new());
     let mut rclone = r.clone();
 
@@ -63,7 +62,7 @@ fn autobase_proof (r: &mut [Poly; R], a: &[PolyVecM; R], idx: usize) {
     r[3].sigma65_ntt();
 }
 
-fn autobase_verify (f: &mut [Poly; R]) {
+pub fn autobase_verify (f: &mut [Poly; R]) {
     let mut b = [Poly::new(); R];
     let mut fclone = f.clone();
     b[0] = fclone[0].clone();
@@ -80,7 +79,7 @@ fn autobase_verify (f: &mut [Poly; R]) {
     Poly::sub_other(&mut b[1], &btmp[1], &btmp[3]);
     b[1].pointwise_montgomery(&NTTX64);
     Poly::sub_other(&mut b[0], &btmp[0], &btmp[2]);
-    Poly::add_other(&mut f[2], &b[0], &b[1]);
+    Poly::add_other(&mut f[1], &b[0], &b[1]);
     Poly::sub_other(&mut f[3], &b[0], &b[1]);
 
     f[1].sigma193_ntt();
@@ -92,26 +91,11 @@ fn autobase_verify (f: &mut [Poly; R]) {
 pub fn proof (
       msg: &mut PolyVecM, 
         g: &[PolyVecM; R],
-    chash: &[u8; SYMBYTES]
+    alpha: &[Poly; 4],
+     beta: &[Poly; R]
 ) -> Poly {
     let mut v = Poly::new();
-    let mut nonce = 0 as u64;
-    let mut state = Aes256Ctx::init(chash, nonce);
     let mut a: [Poly; R] = std::array::from_fn(|_| Poly::new()); 
-    let alpha: [Poly; 4] = std::array::from_fn(|_| {
-        state.select(nonce);
-        nonce += 1;
-        let mut a = Poly::new();
-        Poly::uniform_preinit(&mut a, &mut state);
-        a
-    });
-    let beta: [Poly; R] = std::array::from_fn(|_| {
-        state.select(nonce);
-        nonce += 1;
-        let mut a = Poly::new();
-        Poly::uniform_preinit(&mut a, &mut state);
-        a
-    });
 
     msg.vec[M-2] = Poly::new();
     let mut tmp = Poly::new();
@@ -151,28 +135,13 @@ pub fn proof (
 
 pub fn verify (
         v: &mut Poly,
-    chash: &[u8; SYMBYTES],
+    alpha: &[Poly; 4],
+     beta: &[Poly; R],
         c: &[Poly; R],
         z: &[CommRnd; R],
-       tp: &Comm,
+       tp: &ExpandedComm,
       ckp: &CommKey,
 ) -> bool {
-    let mut nonce = 0;
-    let mut state = Aes256Ctx::init(&chash, nonce);
-    let alpha: [Poly; 4] = std::array::from_fn(|_| {
-        state.select(nonce);
-        nonce += 1;
-        let mut a = Poly::new();
-        Poly::uniform_preinit(&mut a, &mut state);
-        a
-    });
-    let beta: [Poly; R] = std::array::from_fn(|_| {
-        state.select(nonce);
-        nonce += 1;
-        let mut a = Poly::new();
-        Poly::uniform_preinit(&mut a, &mut state);
-        a
-    });
     let mut zshat: [PolyVecL; R] = std::array::from_fn(|i| { z[i].s  });
     let mut zmhat: [PolyVecM; R] = std::array::from_fn(|i| { z[i].em });
     let mut chat : [Poly; R]     = std::array::from_fn(|i| { c[i] });
@@ -1,16 +1,16 @@
 #![allow(dead_code)]
 #![allow(unused_assignments)]
 
-use bytemuck::{bytes_of, cast_slice};
 use rand::{rngs::OsRng, RngCore};
+use zeroize::Zeroizing;
 
 use crate::{
     add,
     comm::{
-        commitment::{Comm, CommKey, CommRnd}, 
+        commitment::{Comm, CommKey, CommRnd, CompressedComm, ExpandedComm}, 
         opening
     }, 
-    crypto::shake::{Shake128, SHAKE128_RATE}, 
+    crypto::transcript::{self, Transcript}, 
     params::{K, L, M, N, R, SYMBYTES}, 
     poly_arith::{
         poly::Poly, 
@@ -18,28 +18,21 @@ use crate::{
     }
 };
 
+const DOMAIN: &[u8] = b"irelzk-rs addition proof";
+
 pub struct Proof {
     h: Poly,
-    c: [Poly; R],
+    chash: [u8; N/4],
     z: [CommRnd; R],
 }
 
 impl Proof {
-    pub fn prove (
-        rho: &[u8; SYMBYTES],
+    pub fn encode (
+        msg: &mut PolyVecM,
           a: &[u64; 2],
           b: &[u64; 2],
-    ) -> (Proof, Comm) 
-    {
-        let mut nonce = 0;
-        let mut seed  = [0u8; SYMBYTES];
-        let mut thash = [0u8; SYMBYTES];
-        let mut chash = [0u8; SHAKE128_RATE];
-
-        OsRng.fill_bytes(&mut seed);
+    ) {
         let mut x = 0;
-        
-        let mut msg = PolyVecM::new();
         for i in 0..64 {
             let f = ((a[0] >> i) & 1) as i32;
             let g = ((b[0] >> i) & 1) as i32;
@@ -60,6 +53,30 @@ impl Proof {
             x >>= 1;
             msg.vec[3].coeffs[64 + i] = x;
         }
+    }
+
+    pub fn prove (
+        rho: &[u8; SYMBYTES],
+          a: &[u64; 2],
+          b: &[u64; 2],
+    ) -> (Proof, CompressedComm) 
+    {
+        Self::prove_with_key(&CommKey::expand(rho), a, b)
+    }
+
+    pub fn prove_with_key (
+         ck: &CommKey,
+          a: &[u64; 2],
+          b: &[u64; 2],
+    ) -> (Proof, CompressedComm) 
+    {
+        let mut nonce = 0;
+        let mut seed  = Zeroizing::new([0u8; SYMBYTES]);
+
+        OsRng.fill_bytes(seed.as_mut());
+        
+        let mut msg = Zeroizing::new(PolyVecM::new());
+        Self::encode(&mut msg, a, b);
         
         Poly::uniform_random(&mut msg.vec[M-1], &seed, nonce);
         nonce += 1;
@@ -68,28 +85,12 @@ impl Proof {
         }
         msg.vec[M-1].ntt();
 
-        let ck = CommKey::expand(&rho);
-        let mut r = CommRnd::generate();
-        let mut t = Comm::commit(&ck, &mut r, &msg);
-
-        t.t0.vec_inverse_ntt();
-        let mut t0low = PolyVecK::new();
-        PolyVecK::vec_power2round(&mut t.t0, &mut t0low);
-        t.t0.vec_ntt();
-        t0low.vec_ntt();
-       
-        let tmslice: &[Poly] = &t.tm.vec[..(M-1)]; 
-        let t0bytes: &[u8] = bytes_of(&t.t0);
-        let tmbytes: &[u8] = cast_slice(tmslice);
-        let lastbytes: &[u8] = bytes_of(&t.tm.vec[M-1]);
-
-        let mut shake128_state = Shake128::init();
-        shake128_state.absorb(rho);
-        shake128_state.absorb(t0bytes);
-        shake128_state.absorb(tmbytes);
-        shake128_state.absorb(lastbytes);
-        shake128_state.finalize();
-        shake128_state.squeeze(&mut thash);
+        let r = CommRnd::generate();
+        let rhat = r.to_ntt();
+        let (mut tc, t0low) = Comm::commit(ck, &r, &msg).power2round();
+        let t = tc.expand();
+
+        let statement = Self::statement(ck, &t);
 
         let mut tmp = Poly::new();
         let mut c = [Poly::new(); R];
@@ -97,110 +98,99 @@ impl Proof {
         let mut h = Poly::new();
 
         loop {
+            opening::reseed(&mut seed, &mut nonce, 1);
             let mut  w1: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
             let mut   g: [PolyVecM; R] = std::array::from_fn(|_| PolyVecM::new());
             let mut vpr = [Poly::new(); R];
             let mut   y = opening::generate_y(&seed, nonce); 
-            opening::first(&mut w1, &mut g, &mut y, &ck);
+            opening::first(&mut w1, &mut g, &mut y, ck);
             nonce += (R*(K+L+M)) as u16;
-            
-            let w1bytes: &[u8] = cast_slice(&w1);
-            shake128_state = Shake128::init();
-            shake128_state.absorb(&thash);
-            shake128_state.absorb(w1bytes);
-            shake128_state.finalize();
-            shake128_state.squeezeblocks(&mut chash, 1);
-
-            let prod_slice: &[u8; SYMBYTES] = (&chash[..SYMBYTES]).try_into().unwrap();
-            let lin_slice : &[u8; SYMBYTES] = (&chash[SYMBYTES..(2*SYMBYTES)]).try_into().unwrap(); 
-            let v = add::product::proof(&mut msg, &g, prod_slice);
-            Poly::add_other(&mut tmp, &t.tm.vec[M-2], &msg.vec[M-2]); 
-            h = add::linear::proof(&mut vpr, &msg, lin_slice, &g); 
 
-            let vprbytes: &[u8] = cast_slice(&vpr);
-            shake128_state = Shake128::init();
-            shake128_state.absorb(prod_slice);
-            shake128_state.absorb(lin_slice);
-            shake128_state.absorb(bytes_of(&tmp));
-            shake128_state.absorb(bytes_of(&v));
-            shake128_state.absorb(bytes_of(&h));
-            shake128_state.absorb(vprbytes);
-            shake128_state.finalize();
-            shake128_state.squeezeblocks(&mut chash, 1);
+            let mut transcript = statement.clone();
+            transcript.append_polyvecs(b"w1", &w1);
+            let mut xof = transcript.challenge_xof(b"product-linear");
+            let (alpha, beta) = transcript::sample_alpha_beta(&mut xof);
+            let gamma: [Poly; R] = std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof));
 
-            let cslice: &[u8; N/4] = (&chash[..(N/4)]).try_into().unwrap();
-            opening::challenge_prehash(&mut c, cslice);
+            let v = add::product::proof(&mut msg, &g, &alpha, &beta);
+            Poly::add_other(&mut tmp, &t.tm.vec[M-2], &msg.vec[M-2]); 
+            tmp.canonical();
+            h = add::linear::proof(&mut vpr, &msg, &gamma, &g); 
 
+            transcript.append_poly(b"tm", &tmp);
+            transcript.append_poly(b"v", &v);
+            transcript.append_poly(b"h", &h);
+            transcript.append_polys(b"vprime", &vpr);
+            let mut xof = transcript.challenge_xof(b"c");
+            c = transcript::sample_challenge(&mut xof);
 
-            if !opening::last(&mut z, &mut y, &r, &c, &w1, &t0low) {
+            if !opening::last(&mut z, &mut y, &r, &rhat, &c, &w1, &t0low) {
                 break;
             }
         }
-        t.tm.vec[M-2] = tmp.clone();
-        ( Proof { h, c, z }, t )
+        tc.tm.vec[M-2] = tmp;
+        ( Proof { h, chash: opening::challenge_to_bytes(&c).unwrap(), z }, tc )
     }
 
     pub fn verify (
           p: &Proof,
-          t: &Comm,
+          t: &CompressedComm,
         rho: &[u8; SYMBYTES]
     ) -> bool {
-        let mut thash = [0u8; SYMBYTES];
-        let mut chash = [0u8; SHAKE128_RATE];
-        let mut shake128_state = Shake128::init();
-
-        let tmslice: &[Poly] = &t.tm.vec[..(M-1)]; 
-        let t0bytes: &[u8] = bytes_of(&t.t0);
-        let tmbytes: &[u8] = cast_slice(tmslice);
-        let lastbytes: &[u8] = bytes_of(&t.tm.vec[M-1]);
-        shake128_state.absorb(rho);
-        shake128_state.absorb(t0bytes);
-        shake128_state.absorb(tmbytes);
-        shake128_state.absorb(lastbytes);
-        shake128_state.finalize();
-        shake128_state.squeeze(&mut thash);
-
-        let ck = CommKey::expand(rho);
+        Self::verify_with_key(p, t, &CommKey::expand(rho))
+    }
+
+    pub fn verify_with_key (
+          p: &Proof,
+          t: &CompressedComm,
+         ck: &CommKey
+    ) -> bool {
+        if t.key_id != ck.id {
+            return true
+        }
+        let t = &t.expand();
+        let mut transcript = Self::statement(ck, t);
+        let c = opening::challenge_from_bytes(&p.chash);
+
         let mut w1: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
-        if opening::verify_first(&mut w1, &p.c, &p.z, t, &ck) {
+        if opening::verify_first(&mut w1, &c, &p.z, t, ck) {
             return true
         }
 
-        let w1bytes: &[u8] = cast_slice(&w1);
-        shake128_state = Shake128::init();
-        shake128_state.absorb(&thash);
-        shake128_state.absorb(&w1bytes);
-        shake128_state.finalize();
-        shake128_state.squeezeblocks(&mut chash, 1);
+        transcript.append_polyvecs(b"w1", &w1);
+        let mut xof = transcript.challenge_xof(b"product-linear");
+        let (alpha, beta) = transcript::sample_alpha_beta(&mut xof);
+        let gamma: [Poly; R] = std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof));
 
         let mut v = Poly::new();
         let mut vpr = [Poly::new(); R];
-        let prod_slice: &[u8; SYMBYTES] = (&chash[..SYMBYTES]).try_into().unwrap();
-        let lin_slice : &[u8; SYMBYTES] = (&chash[SYMBYTES..(2*SYMBYTES)]).try_into().unwrap();
-        if add::product::verify(&mut v, prod_slice, &p.c, &p.z, t, &ck) {
+        if add::product::verify(&mut v, &alpha, &beta, &c, &p.z, t, ck) {
             return true
         }
-        if add::linear::verify(&mut vpr, lin_slice, &p.h, &p.c, &p.z, t, &ck) {
+        if add::linear::verify(&mut vpr, &gamma, &p.h, &c, &p.z, t, ck) {
             return true
         }
 
-
-        let vprbytes: &[u8] = cast_slice(&vpr);
-        shake128_state = Shake128::init();
-        shake128_state.absorb(prod_slice);
-        shake128_state.absorb(lin_slice);
-        shake128_state.absorb(bytes_of(&t.tm.vec[M-2]));
-        shake128_state.absorb(bytes_of(&v));
-        shake128_state.absorb(bytes_of(&p.h));
-        shake128_state.absorb(vprbytes);
-        shake128_state.finalize();
-        shake128_state.squeezeblocks(&mut chash, 1);
-
-        let cslice: &[u8; N/4] = (&chash[..(N/4)]).try_into().unwrap();
-        if opening::verify_last(&p.c, cslice) {
+        transcript.append_poly(b"tm", &t.tm.vec[M-2]);
+        transcript.append_poly(b"v", &v);
+        transcript.append_poly(b"h", &p.h);
+        transcript.append_polys(b"vprime", &vpr);
+        let mut xof = transcript.challenge_xof(b"c");
+        let c2 = transcript::sample_challenge(&mut xof);
+        if opening::verify_last(&p.chash, &c2) {
             return true
         }
 
         false
     }
+
+    /* tm[M-2] is only fixed by the product proof and enters the transcript later */
+    fn statement(ck: &CommKey, t: &ExpandedComm) -> Transcript {
+        let mut transcript = Transcript::new(DOMAIN);
+        transcript.append(b"key-id", &ck.id.0);
+        transcript.append_polys(b"t1", &t.t1.vec);
+        transcript.append_polys(b"tm", &t.tm.vec[..(M-2)]);
+        transcript.append_poly(b"tm-last", &t.tm.vec[M-1]);
+        transcript
+    }
 }
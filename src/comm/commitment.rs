@@ -1,38 +1,115 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+use std::sync::Arc;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 use rand::{rngs::OsRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{
-    crypto::aes256::Aes256Ctx, 
-    params::{K, M, SYMBYTES}, 
+    crypto::{aes256::Aes256Ctx, transcript::Transcript}, 
+    params::{D, GAMMA1, K, L, M, N, PARAMS_ID, Q, SYMBYTES}, 
     poly_arith::{
-        consts::MONTSQ, poly::Poly, polyvec::{PolyVecK, PolyVecL, PolyVecM}
+        consts::MONTSQ,
+        poly::{Poly, POLYT1_PACKEDBYTES, POLYUNIFORM_PACKEDBYTES},
+        polyvec::{PolyVec, PolyVecK, PolyVecL, PolyVecM}
     }
 };
 
 pub struct Comm {
-   pub t0: PolyVecK,
-   pub tm: PolyVecM,
+   pub     t0: PolyVecK,
+   pub     tm: PolyVecM,
+   pub key_id: CommKeyId,
+}
+
+/* High bits of t0 packed at 30-D bits and tm at 30 bits; what verifiers accept */
+pub struct CompressedComm {
+   pub     t1: PolyVecK,
+   pub     tm: PolyVecM,
+   pub key_id: CommKeyId,
 }
 
+/* What the verifiers work with, t1 in the NTT domain, see CompressedComm::expand */
+pub struct ExpandedComm {
+   pub     t1: PolyVecK,
+   pub     tm: PolyVecM,
+   pub key_id: CommKeyId,
+}
+
+pub const COMPRESSED_COMM_BYTES: usize = SYMBYTES + K*POLYT1_PACKEDBYTES + M*POLYUNIFORM_PACKEDBYTES;
+
 pub struct CommRnd {
-    pub  s: PolyVecL,
-    pub  e: PolyVecK,
-    pub em: PolyVecM,
+    pub    s: PolyVecL,
+    pub    e: PolyVecK,
+    pub   em: PolyVecM,
+    /* bound on the infinity norm of s, e and em in the coefficient domain */
+    pub norm: u32,
+}
+
+impl Zeroize for CommRnd {
+    fn zeroize(&mut self) {
+        self.s.zeroize();
+        self.e.zeroize();
+        self.em.zeroize();
+        self.norm.zeroize();
+    }
+}
+
+impl Drop for CommRnd {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
 }
 
+impl ZeroizeOnDrop for CommRnd {}
+
 pub struct CommKey {
-    pub b0: [PolyVecL; K],
-    pub bt: [PolyVecM; K],
-    pub bm: [PolyVecL; M],
+    pub rho: [u8; SYMBYTES],
+    pub  id: CommKeyId,
+    pub  b0: [PolyVecL; K],
+    pub  bt: [PolyVecM; K],
+    pub  bm: [PolyVecL; M],
+}
+
+pub const COMMIT_BLOCK: usize = 8;
+
+pub const COMMKEY_POLYS: usize = K*L + K*M + M*L;
+pub const COMMKEY_BYTES: usize = 2*SYMBYTES + COMMKEY_POLYS*POLYUNIFORM_PACKEDBYTES;
+
+/*
+ * Key derivation: with T = Transcript::new(KEY_DOMAIN) followed by
+ * T.append("params", PARAMS_ID) and T.append("rho", rho), matrix X in
+ * {"b0", "bt", "bm"} is expanded from the 32-byte seed read from
+ * T.challenge_xof(X), entry (i, j) being Poly::uniform_preinit on
+ * Aes256Ctx::init(seed, (i<<16) + j). The key id is read from T.challenge_xof("key-id").
+ */
+const KEY_DOMAIN: &[u8] = b"irelzk-rs commitment key";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CommKeyId(pub [u8; SYMBYTES]);
+
+impl CommKeyId {
+    pub fn derive(rho: &[u8; SYMBYTES]) -> Self {
+        Self(CommKey::derivation(rho).challenge_xof(b"key-id").read_array())
+    }
 }
 
 impl CommKey {
-    pub fn expand(rho: &[u8; SYMBYTES]) -> Self {
-        let mut state = Aes256Ctx::init(rho, 0);
-        let b0: [PolyVecL; K] = std::array::from_fn(|i| {
-            PolyVecL {
+    fn derivation(rho: &[u8; SYMBYTES]) -> Transcript {
+        let mut transcript = Transcript::new(KEY_DOMAIN);
+        transcript.append(b"params", PARAMS_ID);
+        transcript.append(b"rho", rho);
+        transcript
+    }
+
+    fn expand_matrix<const S: usize, const T: usize>(rho: &[u8; SYMBYTES], name: &[u8]) -> [PolyVec<S>; T] {
+        let seed: [u8; SYMBYTES] = Self::derivation(rho).challenge_xof(name).read_array();
+        let mut state = Aes256Ctx::init(&seed, 0);
+        std::array::from_fn(|i| {
+            PolyVec {
                 vec: std::array::from_fn(|j| {
                     let nonce = ((i as u64)<<16) + (j as u64);
                     state.select(nonce);
@@ -41,42 +118,93 @@ impl CommKey {
                     a
                 })
             }
-        });
+        })
+    }
 
-        let bt: [PolyVecM; K] = std::array::from_fn(|i| {
-            PolyVecM {
-                vec: std::array::from_fn(|j| {
-                    let nonce = (((K+i) as u64) << 16) + (j as u64);
-                    state.select(nonce);
-                    let mut a = Poly::new();
-                    Poly::uniform_preinit(&mut a, &mut state);
-                    a
-                })
-            } 
-        });
-        
-        let bm: [PolyVecL; M] = std::array::from_fn(|i| {
-            PolyVecL {
-                vec: std::array::from_fn(|j| {
-                    let nonce = (((2*K+i) as u64) <<16) + (j as u64);
-                    state.select(nonce);
-                    let mut a = Poly::new();
-                    Poly::uniform_preinit(&mut a, &mut state);
-                    a
-                })
-            }
-        });
+    pub fn expand(rho: &[u8; SYMBYTES]) -> Self {
+        Self {
+            rho: *rho,
+             id: CommKeyId::derive(rho),
+             b0: Self::expand_matrix(rho, b"b0"),
+             bt: Self::expand_matrix(rho, b"bt"),
+             bm: Self::expand_matrix(rho, b"bm"),
+        }
+    }
+
+    /* Expanded once per rho, the key can be shared between threads */
+    pub fn shared(rho: &[u8; SYMBYTES]) -> Arc<Self> {
+        Arc::new(Self::expand(rho))
+    }
+
+    fn polys(&self) -> impl Iterator<Item = &Poly> {
+        self.b0.iter().flat_map(|v| v.vec.iter())
+            .chain(self.bt.iter().flat_map(|v| v.vec.iter()))
+            .chain(self.bm.iter().flat_map(|v| v.vec.iter()))
+    }
 
-        Self { b0, bt, bm }
+    fn polys_mut(&mut self) -> impl Iterator<Item = &mut Poly> {
+        self.b0.iter_mut().flat_map(|v| v.vec.iter_mut())
+            .chain(self.bt.iter_mut().flat_map(|v| v.vec.iter_mut()))
+            .chain(self.bm.iter_mut().flat_map(|v| v.vec.iter_mut()))
+    }
+
+    fn digest(rho: &[u8; SYMBYTES], matrices: &[u8]) -> [u8; SYMBYTES] {
+        let mut transcript = Self::derivation(rho);
+        transcript.append(b"matrices", matrices);
+        transcript.challenge_xof(b"digest").read_array()
+    }
+
+    /*
+     * rho, a digest of rho and the matrices, then the NTT domain matrices as
+     * in Poly::pack_uniform. The digest catches a corrupted cache, but anyone
+     * who can write the bytes can recompute it: only load keys from trusted
+     * storage and fall back to CommKey::expand(rho) when this returns None.
+     */
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; COMMKEY_BYTES];
+        bytes[..SYMBYTES].copy_from_slice(&self.rho);
+        for (r, a) in bytes[2*SYMBYTES..].chunks_exact_mut(POLYUNIFORM_PACKEDBYTES).zip(self.polys()) {
+            let mut a = *a;
+            a.canonical();
+            Poly::pack_uniform(r, &a);
+        }
+        let digest = Self::digest(&self.rho, &bytes[2*SYMBYTES..]);
+        bytes[SYMBYTES..2*SYMBYTES].copy_from_slice(&digest);
+        bytes
+    }
+
+    /* Returns None on a wrong length, a digest mismatch or a coefficient outside [0, q) */
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != COMMKEY_BYTES {
+            return None;
+        }
+        let rho: [u8; SYMBYTES] = bytes[..SYMBYTES].try_into().unwrap();
+        if Self::digest(&rho, &bytes[2*SYMBYTES..])[..] != bytes[SYMBYTES..2*SYMBYTES] {
+            return None;
+        }
+        let mut ck = Self {
+            rho,
+             id: CommKeyId::derive(&rho),
+             b0: [PolyVecL::new(); K],
+             bt: [PolyVecM::new(); K],
+             bm: [PolyVecL::new(); M],
+        };
+        for (a, r) in bytes[2*SYMBYTES..].chunks_exact(POLYUNIFORM_PACKEDBYTES).zip(ck.polys_mut()) {
+            if Poly::unpack_uniform(r, a) {
+                return None;
+            }
+        }
+        Some(ck)
     }
 }
 
 impl CommRnd {
     pub fn new() -> Self {
         Self {
-             s: PolyVecL::new(),
-             e: PolyVecK::new(),
-            em: PolyVecM::new(),
+               s: PolyVecL::new(),
+               e: PolyVecK::new(),
+              em: PolyVecM::new(),
+            norm: 0,
         }
     }
 
@@ -85,6 +213,7 @@ impl CommRnd {
         OsRng.fill_bytes(&mut buf);
         let mut nonce = 0;
         let mut state = Aes256Ctx::init(&buf, nonce);
+        buf.zeroize();
         let mut make_vec = || {
             nonce += 1;
             let mut a = Poly::new();
@@ -93,9 +222,10 @@ impl CommRnd {
             a
         };
         Self {
-             s: PolyVecL { vec: std::array::from_fn(|_| make_vec()) },
-             e: PolyVecK { vec: std::array::from_fn(|_| make_vec()) },
-            em: PolyVecM { vec: std::array::from_fn(|_| make_vec()) },
+               s: PolyVecL { vec: std::array::from_fn(|_| make_vec()) },
+               e: PolyVecK { vec: std::array::from_fn(|_| make_vec()) },
+              em: PolyVecM { vec: std::array::from_fn(|_| make_vec()) },
+            norm: 1,
         }
     }    
 
@@ -109,40 +239,244 @@ impl CommRnd {
             a
         };
         Self {
-             s: PolyVecL { vec: std::array::from_fn(|_| make_vec())  },
-             e: PolyVecK { vec: std::array::from_fn(|_| Poly::new()) },
-            em: PolyVecM { vec: std::array::from_fn(|_| make_vec())  },
+               s: PolyVecL { vec: std::array::from_fn(|_| make_vec())  },
+               e: PolyVecK { vec: std::array::from_fn(|_| Poly::new()) },
+              em: PolyVecM { vec: std::array::from_fn(|_| make_vec())  },
+            norm: GAMMA1 as u32,
         }
     }
+
+    /* A copy of the randomness in the NTT domain, as used by the provers */
+    pub fn to_ntt(&self) -> CommRnd {
+        let mut rhat = CommRnd { s: self.s, e: self.e, em: self.em, norm: self.norm };
+        rhat.s.vec_ntt();
+        rhat.e.vec_ntt();
+        rhat.em.vec_ntt();
+        rhat
+    }
+
+    /*
+     * Centered representatives after a combination. The norm is recomputed
+     * rather than accumulated, so a sub that undoes an add gives the original
+     * bound back.
+     */
+    fn normalize(&mut self) {
+        self.s.reduce();
+        self.e.reduce();
+        self.em.reduce();
+        self.s.freeze();
+        self.e.freeze();
+        self.em.freeze();
+        self.norm = PolyVecL::vec_inf_norm(&self.s)
+            .max(PolyVecK::vec_inf_norm(&self.e))
+            .max(PolyVecM::vec_inf_norm(&self.em));
+    }
+
+    /*
+     * The combinators act on the coefficient domain randomness and must be
+     * mirrored on the commitment and the message. They keep the coefficients
+     * centred, opening::last multiplies them by c without reduction.
+     */
+    pub fn add(&mut self, other: &CommRnd) {
+        self.s.add(&other.s);
+        self.e.add(&other.e);
+        self.em.add(&other.em);
+        self.normalize();
+    }
+
+    pub fn sub(&mut self, other: &CommRnd) {
+        self.s.sub(&other.s);
+        self.e.sub(&other.e);
+        self.em.sub(&other.em);
+        self.normalize();
+    }
+
+    pub fn mul_scalar(&mut self, c: i32) {
+        self.s.mul_scalar(c);
+        self.e.mul_scalar(c);
+        self.em.mul_scalar(c);
+        self.normalize();
+    }
+
+    /* c is a small polynomial in the coefficient domain */
+    pub fn mul_poly(&mut self, c: &Poly) {
+        self.s.vec_ntt();
+        self.e.vec_ntt();
+        self.em.vec_ntt();
+        self.s.mul_poly(c);
+        self.e.mul_poly(c);
+        self.em.mul_poly(c);
+        self.s.vec_inverse_ntt();
+        self.e.vec_inverse_ntt();
+        self.em.vec_inverse_ntt();
+        self.normalize();
+    }
 }
 
 impl Comm {
-    pub fn commit(ck: &CommKey, r: &mut CommRnd, msg: &PolyVecM) -> Self {
-        r.s.vec_ntt();
-        r.e.vec_ntt();
-        r.em.vec_ntt();
-        
-        let mut t0: PolyVecK = PolyVecK {
-            vec: std::array::from_fn(|i| {
-                PolyVecL::pointwise_acc_montgomery(&ck.b0[i], &r.s)
-            })
-        };
-        let mut tm: PolyVecM = PolyVecM {
-            vec: std::array::from_fn(|i| {
-                PolyVecL::pointwise_acc_montgomery(&ck.bm[i], &r.s)
-            })
-        };
-        let tag: PolyVecK = PolyVecK {
-            vec: std::array::from_fn(|i| {
-                PolyVecM::pointwise_acc_montgomery(&ck.bt[i], &r.em)
-            })
+    /* r stays in the coefficient domain, msg is in the NTT domain */
+    pub fn commit(ck: &CommKey, r: &CommRnd, msg: &PolyVecM) -> Self {
+        Self::commit_block(ck, std::slice::from_ref(r), std::slice::from_ref(msg)).pop().unwrap()
+    }
+
+    pub fn commit_batch(ck: &CommKey, r: &[CommRnd], msg: &[PolyVecM]) -> Vec<Self> {
+        assert_eq!(r.len(), msg.len());
+        #[cfg(feature = "rayon")]
+        {
+            r.par_chunks(COMMIT_BLOCK).zip(msg.par_chunks(COMMIT_BLOCK))
+                .flat_map_iter(|(r, msg)| Self::commit_block(ck, r, msg))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            r.chunks(COMMIT_BLOCK).zip(msg.chunks(COMMIT_BLOCK))
+                .flat_map(|(r, msg)| Self::commit_block(ck, r, msg))
+                .collect()
+        }
+    }
+
+    /* Every key row is applied to the whole block before moving on to the next one */
+    fn commit_block(ck: &CommKey, r: &[CommRnd], msg: &[PolyVecM]) -> Vec<Self> {
+        let rhat: Vec<CommRnd> = r.iter().map(|r| r.to_ntt()).collect();
+        let mut t: Vec<Self> = msg.iter().map(|_| {
+            Self { t0: PolyVecK::new(), tm: PolyVecM::new(), key_id: ck.id }
+        }).collect();
+
+        for i in 0..K {
+            for (t, r) in t.iter_mut().zip(rhat.iter()) {
+                t.t0.vec[i] = PolyVecL::pointwise_acc_montgomery(&ck.b0[i], &r.s);
+            }
+            for (t, r) in t.iter_mut().zip(rhat.iter()) {
+                let tag = PolyVecM::pointwise_acc_montgomery(&ck.bt[i], &r.em);
+                t.t0.vec[i].add(&tag);
+            }
+        }
+        for i in 0..M {
+            for (t, r) in t.iter_mut().zip(rhat.iter()) {
+                t.tm.vec[i] = PolyVecL::pointwise_acc_montgomery(&ck.bm[i], &r.s);
+            }
+        }
+
+        for ((t, r), msg) in t.iter_mut().zip(rhat.iter()).zip(msg.iter()) {
+            t.t0.scale_montgomery(MONTSQ as i32);
+            t.t0.add(&r.e);
+            t.tm.scale_montgomery(MONTSQ as i32);
+            t.tm.add(&r.em);
+            t.tm.add(msg);
+        }
+        t
+    }
+
+    /* Returns true unless (r, msg) opens the commitment and |r|_inf <= bound */
+    pub fn verify_opening(&self, ck: &CommKey, r: &CommRnd, msg: &PolyVecM, bound: u32) -> bool {
+        if self.key_id != ck.id {
+            return true;
+        }
+        if PolyVecL::vec_check_norm(&r.s, bound + 1)
+            || PolyVecK::vec_check_norm(&r.e, bound + 1)
+            || PolyVecM::vec_check_norm(&r.em, bound + 1) {
+            return true;
+        }
+
+        let mut t = Self::commit(ck, r, msg);
+        t.t0.sub(&self.t0);
+        t.tm.sub(&self.tm);
+        t.t0.reduce();
+        t.tm.reduce();
+        t.t0.vec.iter().chain(t.tm.vec.iter()).any(|p| p.coeffs.iter().any(|&x| x % Q != 0))
+    }
+
+    pub fn add(&mut self, other: &Comm) {
+        assert_eq!(self.key_id, other.key_id, "commitments under different keys");
+        self.t0.add(&other.t0);
+        self.tm.add(&other.tm);
+        self.t0.reduce();
+        self.tm.reduce();
+    }
+
+    pub fn sub(&mut self, other: &Comm) {
+        assert_eq!(self.key_id, other.key_id, "commitments under different keys");
+        self.t0.sub(&other.t0);
+        self.tm.sub(&other.tm);
+        self.t0.reduce();
+        self.tm.reduce();
+    }
+
+    pub fn mul_scalar(&mut self, c: i32) {
+        self.t0.mul_scalar(c);
+        self.tm.mul_scalar(c);
+    }
+
+    pub fn mul_poly(&mut self, c: &Poly) {
+        self.t0.mul_poly(c);
+        self.tm.mul_poly(c);
+    }
+}
+
+impl Comm {
+    /* Returns the compressed commitment and the low bits of t0 in the coefficient domain */
+    pub fn power2round(&self) -> (CompressedComm, PolyVecK) {
+        let mut t1 = self.t0;
+        let mut t0low = PolyVecK::new();
+        t1.vec_inverse_ntt();
+        PolyVecK::vec_power2round(&mut t1, &mut t0low);
+
+        let mut tm = self.tm;
+        for a in tm.vec.iter_mut() {
+            a.canonical();
+        }
+        (CompressedComm { t1, tm, key_id: self.key_id }, t0low)
+    }
+
+    pub fn compress(&self) -> CompressedComm {
+        self.power2round().0
+    }
+}
+
+impl CompressedComm {
+    /* A separate type from Comm, 2^D t1 only approximates t0 */
+    pub fn expand(&self) -> ExpandedComm {
+        let mut t1 = self.t1;
+        t1.vec_ntt();
+        ExpandedComm { t1, tm: self.tm, key_id: self.key_id }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; COMPRESSED_COMM_BYTES];
+        bytes[..SYMBYTES].copy_from_slice(&self.key_id.0);
+        let (t1bytes, tmbytes) = bytes[SYMBYTES..].split_at_mut(K*POLYT1_PACKEDBYTES);
+        for (r, a) in t1bytes.chunks_exact_mut(POLYT1_PACKEDBYTES).zip(self.t1.vec.iter()) {
+            Poly::pack_t1(r, a);
+        }
+        for (r, a) in tmbytes.chunks_exact_mut(POLYUNIFORM_PACKEDBYTES).zip(self.tm.vec.iter()) {
+            let mut a = *a;
+            a.canonical();
+            Poly::pack_uniform(r, &a);
+        }
+        bytes
+    }
+
+    /* Returns None on a wrong length or on a non canonical encoding */
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != COMPRESSED_COMM_BYTES {
+            return None;
+        }
+        let mut t = Self {
+                t1: PolyVecK::new(),
+                tm: PolyVecM::new(),
+            key_id: CommKeyId(bytes[..SYMBYTES].try_into().unwrap()),
         };
-        t0.add(&tag);
-        t0.scale_montgomery(MONTSQ as i32);
-        t0.add(&r.e);
-        tm.scale_montgomery(MONTSQ as i32);
-        tm.add(&r.em);
-        tm.add(msg);
-        Self { t0, tm }
+        let (t1bytes, tmbytes) = bytes[SYMBYTES..].split_at(K*POLYT1_PACKEDBYTES);
+        for (a, r) in t1bytes.chunks_exact(POLYT1_PACKEDBYTES).zip(t.t1.vec.iter_mut()) {
+            if Poly::unpack_t1(r, a) {
+                return None;
+            }
+        }
+        for (a, r) in tmbytes.chunks_exact(POLYUNIFORM_PACKEDBYTES).zip(t.tm.vec.iter_mut()) {
+            if Poly::unpack_uniform(r, a) {
+                return None;
+            }
+        }
+        Some(t)
     }
 }
@@ -0,0 +1,129 @@
+#![allow(dead_code)]
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+    params::{M, N, Q},
+    poly_arith::polyvec::PolyVecM
+};
+
+/* u64s and bytes are split into 16-bit limbs, leaving room for 2^13 additions */
+pub const LIMB_BITS: usize = 16;
+pub const LIMBS_PER_U64: usize = 64 / LIMB_BITS;
+pub const CAPACITY: usize = M * N;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Domain {
+    /* values are polynomial coefficients, the message is NTT'd before committing */
+    Coeff,
+    /* values are the NTT slots themselves, as needed by the product proof */
+    Ntt,
+}
+
+pub struct Message {
+    /* always in the NTT domain, ready for Comm::commit */
+    pub msg: PolyVecM,
+    pub domain: Domain,
+    pub len: usize,
+}
+
+impl Zeroize for Message {
+    fn zeroize(&mut self) {
+        self.msg.zeroize();
+        self.len.zeroize();
+    }
+}
+
+impl Drop for Message {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Message {}
+
+impl Message {
+    /* Returns None if the values do not fit into the M*N slots or are not in [0, q) */
+    pub fn from_zq_coeffs(coeffs: &[i32], domain: Domain) -> Option<Self> {
+        if coeffs.len() > CAPACITY || coeffs.iter().any(|&x| !(0..Q).contains(&x)) {
+            return None;
+        }
+        let mut msg = PolyVecM::new();
+        for (i, &x) in coeffs.iter().enumerate() {
+            msg.vec[i / N].coeffs[i % N] = x;
+        }
+        if domain == Domain::Coeff {
+            msg.vec_ntt();
+        }
+        Some(Self { msg, domain, len: coeffs.len() })
+    }
+
+    pub fn from_bits(bits: &[bool], domain: Domain) -> Option<Self> {
+        let coeffs: Vec<i32> = bits.iter().map(|&b| b as i32).collect();
+        Self::from_zq_coeffs(&coeffs, domain)
+    }
+
+    pub fn from_u64s(v: &[u64], domain: Domain) -> Option<Self> {
+        let coeffs: Vec<i32> = v.iter().flat_map(|&x| {
+            (0..LIMBS_PER_U64).map(move |k| ((x >> (LIMB_BITS * k)) & 0xFFFF) as i32)
+        }).collect();
+        let mut m = Self::from_zq_coeffs(&coeffs, domain)?;
+        m.len = v.len();
+        Some(m)
+    }
+
+    /* Two bytes per slot, little endian, an odd length is padded with zero */
+    pub fn from_bytes(bytes: &[u8], domain: Domain) -> Option<Self> {
+        let coeffs: Vec<i32> = bytes.chunks(2).map(|b| {
+            b[0] as i32 | (*b.get(1).unwrap_or(&0) as i32) << 8
+        }).collect();
+        let mut m = Self::from_zq_coeffs(&coeffs, domain)?;
+        m.len = bytes.len();
+        Some(m)
+    }
+
+    /* Canonical representatives in [0, q) of the first n slots */
+    fn slots(&self, n: usize) -> Vec<i32> {
+        let mut msg = self.msg;
+        if self.domain == Domain::Coeff {
+            msg.vec_inverse_ntt();
+        }
+        (0..n).map(|i| msg.vec[i / N].coeffs[i % N].rem_euclid(Q)).collect()
+    }
+
+    pub fn to_zq_coeffs(&self) -> Vec<i32> {
+        self.slots(self.len)
+    }
+
+    /* Returns None if a slot is not a bit, e.g. after a homomorphic addition */
+    pub fn to_bits(&self) -> Option<Vec<bool>> {
+        self.slots(self.len).iter().map(|&x| match x {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }).collect()
+    }
+
+    /* Limbs that overflowed 16 bits carry into the next one, so sums wrap mod 2^64 */
+    pub fn to_u64s(&self) -> Vec<u64> {
+        self.slots(LIMBS_PER_U64 * self.len).chunks(LIMBS_PER_U64).map(|limbs| {
+            limbs.iter().enumerate().fold(0u64, |acc, (k, &x)| {
+                acc.wrapping_add((x as u64) << (LIMB_BITS * k))
+            })
+        }).collect()
+    }
+
+    /* Returns None if a limb overflowed 16 bits */
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.len + 1);
+        for x in self.slots(self.len.div_ceil(2)) {
+            if x > 0xFFFF {
+                return None;
+            }
+            bytes.push(x as u8);
+            bytes.push((x >> 8) as u8);
+        }
+        bytes.truncate(self.len);
+        Some(bytes)
+    }
+}
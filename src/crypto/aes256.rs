@@ -1,10 +1,15 @@
 use std::arch::x86_64::*;
 
+use rand_core::{CryptoRng, Error, RngCore, SeedableRng};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
 #[repr(C, align(16))]
 #[allow(dead_code)]
 pub struct Aes256Ctx {
     pub rkeys: [__m128i; 16],
     pub n: __m128i,
+    buf: [u8; AES256CTR_BLOCKBYTES],
+    pos: usize,
 }
 
 #[allow(dead_code)]
@@ -97,7 +102,12 @@ impl Aes256Ctx {
 
             rkeys[idx] = temp0;
 
-            Aes256Ctx { rkeys, n }
+            Aes256Ctx { 
+                rkeys, 
+                n, 
+                buf: [0u8; AES256CTR_BLOCKBYTES], 
+                pos: AES256CTR_BLOCKBYTES 
+            }
         }
     } 
 
@@ -122,6 +132,8 @@ impl Aes256Ctx {
     
     pub fn select(&mut self, nonce: u64) {
         unsafe {self.n = _mm_loadl_epi64(&nonce as *const u64 as *const __m128i);}
+        /* Buffered RngCore output belongs to the previous stream */
+        self.pos = AES256CTR_BLOCKBYTES;
     }
 
     unsafe fn encrypt4 (&mut self, out: &mut [u8; 64]) {
@@ -178,3 +190,70 @@ impl Aes256Ctx {
     }
 }
 
+impl Drop for Aes256Ctx {
+    fn drop(&mut self) {
+        self.rkeys.zeroize();
+        self.n.zeroize();
+        self.buf.zeroize();
+        self.pos = 0;
+    }
+}
+
+impl ZeroizeOnDrop for Aes256Ctx {}
+
+impl RngCore for Aes256Ctx {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut out = dest;
+
+        /* Drain leftover keystream from the previous call first */
+        let n = usize::min(AES256CTR_BLOCKBYTES - self.pos, out.len());
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        out = &mut out[n..];
+
+        let mut chunks = out.chunks_exact_mut(AES256CTR_BLOCKBYTES);
+        for chunk in &mut chunks {
+            unsafe {
+                self.encrypt4(chunk.try_into().unwrap());
+            }
+        }
+
+        let rem = chunks.into_remainder();
+        if !rem.is_empty() {
+            let mut buf = [0u8; AES256CTR_BLOCKBYTES];
+            unsafe {
+                self.encrypt4(&mut buf);
+            }
+            self.buf = buf;
+            rem.copy_from_slice(&self.buf[..rem.len()]);
+            self.pos = rem.len();
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for Aes256Ctx {}
+
+impl SeedableRng for Aes256Ctx {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Aes256Ctx::init(&seed, 0)
+    }
+}
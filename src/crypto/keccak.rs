@@ -1,10 +1,28 @@
 #![allow(dead_code)]
 #![allow(non_snake_case)]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Clone)]
 pub struct KeccakState {
     pub s: [u64; 25],
     pub pos: usize,
 }
 
+impl Zeroize for KeccakState {
+    fn zeroize(&mut self) {
+        self.s.zeroize();
+        self.pos.zeroize();
+    }
+}
+
+impl Drop for KeccakState {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for KeccakState {}
+
 const NROUNDS: usize = 24;
 pub const KECCAK_F_ROUND_CONSTANTS: [u64; NROUNDS] = [
     0x0000000000000001,
@@ -1,11 +1,23 @@
 #![allow(dead_code)]
-use crate::crypto::keccak::KeccakState;
+use rand_core::{CryptoRng, Error, RngCore};
+
+use crate::{
+    crypto::keccak::KeccakState, 
+    params::N, 
+    poly_arith::poly::Poly
+};
 
 pub const SHAKE128_RATE: usize = 168;
+
+#[derive(Clone)]
 pub struct Shake128 {
     state: KeccakState,
 }
 
+pub struct XofReader {
+    xof: Shake128,
+}
+
 impl Shake128 {
     pub fn init() -> Self {
         Self {
@@ -50,6 +62,11 @@ impl Shake128 {
         );
     }
 
+    pub fn finalize_xof(mut self) -> XofReader {
+        self.finalize();
+        XofReader { xof: self }
+    }
+
     pub fn hash(out: &mut [u8], input: &[u8]) {
         let mut state = Shake128::init();
         state.absorb(input);
@@ -57,3 +74,54 @@ impl Shake128 {
         state.squeeze(out);
     }
 }
+
+impl XofReader {
+    pub fn read(&mut self, out: &mut [u8]) {
+        self.xof.squeeze(out);
+    }
+
+    pub fn read_array<const S: usize>(&mut self) -> [u8; S] {
+        let mut out = [0u8; S];
+        self.read(&mut out);
+        out
+    }
+
+    pub fn read_u32_le(&mut self) -> u32 {
+        u32::from_le_bytes(self.read_array())
+    }
+
+    pub fn read_u64_le(&mut self) -> u64 {
+        u64::from_le_bytes(self.read_array())
+    }
+
+    pub fn read_poly_uniform(&mut self, r: &mut Poly) {
+        let mut buf = [0u8; 4*N];
+        let mut ctr = 0;
+        while ctr < N {
+            let len = 4*(N - ctr);
+            self.read(&mut buf[..len]);
+            ctr += Poly::rej_uniform(&mut r.coeffs[ctr..], &buf[..len]);
+        }
+    }
+}
+
+impl RngCore for XofReader {
+    fn next_u32(&mut self) -> u32 {
+        self.read_u32_le()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.read_u64_le()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.read(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.read(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for XofReader {}
@@ -0,0 +1,126 @@
+#![allow(dead_code)]
+use crate::{
+    comm::opening::challenge_prehash,
+    crypto::shake::{Shake128, XofReader},
+    params::{ChallengeDist, CHALLENGE_DIST, N, R},
+    poly_arith::{
+        poly::{Poly, POLYUNIFORM_PACKEDBYTES},
+        polyvec::PolyVec
+    }
+};
+
+#[derive(Clone)]
+pub struct Transcript {
+    state: Shake128,
+}
+
+impl Transcript {
+    pub fn new(label: &[u8]) -> Self {
+        let mut state = Shake128::init();
+        state.absorb(&(label.len() as u64).to_le_bytes());
+        state.absorb(label);
+        Self { state }
+    }
+
+    pub fn append(&mut self, label: &[u8], msg: &[u8]) {
+        self.state.absorb(&(label.len() as u64).to_le_bytes());
+        self.state.absorb(label);
+        self.state.absorb(&(msg.len() as u64).to_le_bytes());
+        self.state.absorb(msg);
+    }
+
+    /* Polynomials are absorbed as their canonical representatives, see encode_polys */
+    pub fn append_poly(&mut self, label: &[u8], a: &Poly) {
+        self.append(label, &encode_polys([a]));
+    }
+
+    pub fn append_polys(&mut self, label: &[u8], a: &[Poly]) {
+        self.append(label, &encode_polys(a));
+    }
+
+    pub fn append_polyvecs<const S: usize>(&mut self, label: &[u8], a: &[PolyVec<S>]) {
+        self.append(label, &encode_polys(a.iter().flat_map(|v| v.vec.iter())));
+    }
+
+    /* Every challenge is bound to all previous challenges through the label */
+    pub fn challenge_xof(&mut self, label: &[u8]) -> XofReader {
+        self.append(b"challenge", label);
+        self.state.clone().finalize_xof()
+    }
+}
+
+/*
+ * Coefficients in [0, q) packed at 30 bits little endian, so hashes do not
+ * depend on the host byte order or on how far a backend reduced its values
+ */
+pub fn encode_polys<'a>(polys: impl IntoIterator<Item = &'a Poly>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for a in polys {
+        let mut a = *a;
+        a.canonical();
+        let n = bytes.len();
+        bytes.resize(n + POLYUNIFORM_PACKEDBYTES, 0);
+        Poly::pack_uniform(&mut bytes[n..], &a);
+    }
+    bytes
+}
+
+pub fn sample_uniform_poly(xof: &mut XofReader) -> Poly {
+    let mut a = Poly::new();
+    xof.read_poly_uniform(&mut a);
+    a
+}
+
+pub fn sample_alpha_beta(xof: &mut XofReader) -> ([Poly; 4], [Poly; R]) {
+    let alpha: [Poly; 4] = std::array::from_fn(|_| sample_uniform_poly(xof));
+    let beta : [Poly; R] = std::array::from_fn(|_| sample_uniform_poly(xof));
+    (alpha, beta)
+}
+
+/* Challenge from the distribution selected by params::CHALLENGE_DIST */
+pub fn sample_challenge(xof: &mut XofReader) -> [Poly; R] {
+    match CHALLENGE_DIST {
+        ChallengeDist::Prehash => sample_ternary_challenge(xof, None),
+        ChallengeDist::FixedWeight(tau) => sample_ternary_challenge(xof, Some(tau)),
+    }
+}
+
+pub fn sample_ternary_challenge(xof: &mut XofReader, weight: Option<usize>) -> [Poly; R] {
+    let mut c: [Poly; R] = std::array::from_fn(|_| Poly::new());
+    match weight {
+        None => {
+            let chash: [u8; N/4] = xof.read_array();
+            challenge_prehash(&mut c, &chash);
+        }
+        Some(tau) => {
+            for ci in c.iter_mut() {
+                sample_in_ball(ci, xof, tau);
+            }
+        }
+    }
+    c
+}
+
+/*
+ * Exactly tau of the N/R slots 16*j + k*R of the challenge layout get a +-1,
+ * placed by an inside-out Fisher-Yates shuffle as in Dilithium's SampleInBall.
+ */
+fn sample_in_ball(c: &mut Poly, xof: &mut XofReader, tau: usize) {
+    const SLOTS: usize = N/R;
+    assert!(tau <= SLOTS && 256 % SLOTS == 0);
+    let slot = |s: usize| 16 * (s / 4) + (s % 4) * R;
+
+    let mut signs = xof.read_u64_le();
+    for i in (SLOTS - tau)..SLOTS {
+        let mut j;
+        loop {
+            j = xof.read_array::<1>()[0] as usize % SLOTS;
+            if j <= i {
+                break;
+            }
+        }
+        c.coeffs[slot(i)] = c.coeffs[slot(j)];
+        c.coeffs[slot(j)] = 1 - 2 * (signs & 1) as i32;
+        signs >>= 1;
+    }
+}
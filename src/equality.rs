@@ -0,0 +1,181 @@
+#![allow(dead_code)]
+
+use rand::{rngs::OsRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::{
+    add::product::poly_shift,
+    comm::{
+        commitment::{Comm, CommKey, CommRnd, CompressedComm, ExpandedComm},
+        opening
+    },
+    crypto::transcript::{self, Transcript},
+    params::{K, L, M, N, Q, R, SYMBYTES},
+    poly_arith::{
+        poly::Poly,
+        polyvec::{PolyVecK, PolyVecM}
+    }
+};
+
+const DOMAIN: &[u8] = b"irelzk-rs equality proof";
+
+/* An opening of t together with the message slot that is claimed equal */
+pub struct Witness<'a> {
+    pub   ck: &'a CommKey,
+    pub    t: &'a Comm,
+    pub    r: &'a CommRnd,
+    pub  msg: &'a PolyVecM,
+    pub slot: usize,
+}
+
+pub struct EqualityProof {
+    chash: [u8; N/4],
+    z1: [CommRnd; R],
+    z2: [CommRnd; R],
+}
+
+impl EqualityProof {
+    /* None if the randomness of either commitment is above opening::MAX_RND_NORM */
+    pub fn prove(a: &Witness, b: &Witness) -> Option<Self> {
+        assert!(a.slot < M && b.slot < M);
+        let mut diff = a.msg.vec[a.slot];
+        diff.sub(&b.msg.vec[b.slot]);
+        diff.reduce();
+        assert!(diff.coeffs.iter().all(|&x| x % Q == 0), "the message slots differ");
+        Self::prove_unchecked(a, b)
+    }
+
+    /* prove without the equality check, only to test that the verifier catches a false statement */
+    #[doc(hidden)]
+    pub fn prove_unchecked(a: &Witness, b: &Witness) -> Option<Self> {
+        assert!(a.slot < M && b.slot < M);
+        assert!(!a.t.verify_opening(a.ck, a.r, a.msg, a.r.norm), "invalid opening of the first commitment");
+        assert!(!b.t.verify_opening(b.ck, b.r, b.msg, b.r.norm), "invalid opening of the second commitment");
+        if a.r.norm > opening::MAX_RND_NORM || b.r.norm > opening::MAX_RND_NORM {
+            return None;
+        }
+
+        let mut nonce = 0;
+        let mut seed  = Zeroizing::new([0u8; SYMBYTES]);
+        OsRng.fill_bytes(seed.as_mut());
+
+        let (tc1, t0low1) = a.t.power2round();
+        let (tc2, t0low2) = b.t.power2round();
+        let (t1, t2) = (tc1.expand(), tc2.expand());
+        let statement = Self::statement(a.ck, &t1, a.slot, b.ck, &t2, b.slot);
+        let (rhat1, rhat2) = (a.r.to_ntt(), b.r.to_ntt());
+
+        let mut z1: [CommRnd; R] = std::array::from_fn(|_| CommRnd::new());
+        let mut z2: [CommRnd; R] = std::array::from_fn(|_| CommRnd::new());
+        loop {
+            opening::reseed(&mut seed, &mut nonce, 2);
+            let mut w11: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+            let mut w12: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+            let mut  g1: [PolyVecM; R] = std::array::from_fn(|_| PolyVecM::new());
+            let mut  g2: [PolyVecM; R] = std::array::from_fn(|_| PolyVecM::new());
+            let mut  y1 = opening::generate_y(&seed, nonce);
+            nonce += (R*(K+L+M)) as u16;
+            let mut  y2 = opening::generate_y(&seed, nonce);
+            nonce += (R*(K+L+M)) as u16;
+            opening::first(&mut w11, &mut g1, &mut y1, a.ck);
+            opening::first(&mut w12, &mut g2, &mut y2, b.ck);
+
+            let mut transcript = statement.clone();
+            transcript.append_polyvecs(b"w1-1", &w11);
+            transcript.append_polyvecs(b"w1-2", &w12);
+            let mut xof = transcript.challenge_xof(b"gamma");
+            let gamma: [Poly; R] = std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof));
+
+            let d: [Poly; R] = std::array::from_fn(|k| {
+                let mut d = Poly::new();
+                Poly::sub_other(&mut d, &g1[k].vec[a.slot], &g2[k].vec[b.slot]);
+                d
+            });
+            let vpr = Self::trace(&gamma, &d);
+
+            transcript.append_polys(b"vprime", &vpr);
+            let mut xof = transcript.challenge_xof(b"c");
+            let c = transcript::sample_challenge(&mut xof);
+
+            if !opening::last(&mut z1, &mut y1, a.r, &rhat1, &c, &w11, &t0low1)
+                && !opening::last(&mut z2, &mut y2, b.r, &rhat2, &c, &w12, &t0low2) {
+                return Some(Self { chash: opening::challenge_to_bytes(&c).unwrap(), z1, z2 });
+            }
+        }
+    }
+
+    pub fn verify (
+        &self,
+         ck1: &CommKey,
+          t1: &CompressedComm,
+       slot1: usize,
+         ck2: &CommKey,
+          t2: &CompressedComm,
+       slot2: usize
+    ) -> bool {
+        if slot1 >= M || slot2 >= M || t1.key_id != ck1.id || t2.key_id != ck2.id {
+            return true;
+        }
+        let (t1, t2) = (t1.expand(), t2.expand());
+        let mut transcript = Self::statement(ck1, &t1, slot1, ck2, &t2, slot2);
+        let c = opening::challenge_from_bytes(&self.chash);
+
+        let mut w11: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+        let mut w12: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+        if opening::verify_first(&mut w11, &c, &self.z1, &t1, ck1)
+            || opening::verify_first(&mut w12, &c, &self.z2, &t2, ck2) {
+            return true;
+        }
+
+        transcript.append_polyvecs(b"w1-1", &w11);
+        transcript.append_polyvecs(b"w1-2", &w12);
+        let mut xof = transcript.challenge_xof(b"gamma");
+        let gamma: [Poly; R] = std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof));
+
+        /* B_m z_s + z_m - c t_m equals the masked slot g - c m, so the difference is g1 - g2 */
+        let d: [Poly; R] = std::array::from_fn(|k| {
+            let mut chat = c[k];
+            chat.ntt();
+            let mut d = opening::masked_slot(ck1, &t1, slot1, &chat, &self.z1[k]);
+            d.sub(&opening::masked_slot(ck2, &t2, slot2, &chat, &self.z2[k]));
+            d
+        });
+        let vpr = Self::trace(&gamma, &d);
+
+        transcript.append_polys(b"vprime", &vpr);
+        let mut xof = transcript.challenge_xof(b"c");
+        let c2 = transcript::sample_challenge(&mut xof);
+        opening::verify_last(&self.chash, &c2)
+    }
+
+    /* Slot-wise relation folded with gamma and traced down, as in add::linear */
+    fn trace(gamma: &[Poly; R], d: &[Poly; R]) -> [Poly; R] {
+        std::array::from_fn(|k| {
+            let mut v = Poly::new();
+            for (i, gamma) in gamma.iter().enumerate() {
+                let mut tmp = Poly::new();
+                Poly::pointwise_montgomery_other(&mut tmp, gamma, &d[k]);
+                tmp.trace65_ntt();
+                let tmp2 = tmp;
+                poly_shift(&mut tmp, &tmp2, i);
+                v.add(&tmp);
+            }
+            v.reduce();
+            v.canonical();
+            v
+        })
+    }
+
+    fn statement(ck1: &CommKey, t1: &ExpandedComm, slot1: usize, ck2: &CommKey, t2: &ExpandedComm, slot2: usize) -> Transcript {
+        let mut transcript = Transcript::new(DOMAIN);
+        transcript.append(b"key-id-1", &ck1.id.0);
+        transcript.append_polys(b"t1-1", &t1.t1.vec);
+        transcript.append_polys(b"tm-1", &t1.tm.vec);
+        transcript.append(b"slot-1", &(slot1 as u64).to_le_bytes());
+        transcript.append(b"key-id-2", &ck2.id.0);
+        transcript.append_polys(b"t1-2", &t2.t1.vec);
+        transcript.append_polys(b"tm-2", &t2.tm.vec);
+        transcript.append(b"slot-2", &(slot2 as u64).to_le_bytes());
+        transcript
+    }
+}
@@ -4,6 +4,7 @@ pub mod crypto {
     pub mod aes256;
     pub mod keccak;
     pub mod shake;
+    pub mod transcript;
 }
 pub mod poly_arith {
     pub mod poly;
@@ -14,6 +15,7 @@ pub mod poly_arith {
 }
 pub mod comm {
     pub mod commitment;
+    pub mod message;
     pub mod opening;
 }
 pub mod add {
@@ -21,3 +23,5 @@ pub mod add {
     pub mod product;
 }
 pub mod addition;
+pub mod equality;
+pub mod shuffle;
@@ -3,6 +3,7 @@ pub mod crypto {
     pub mod aes256;
     pub mod shake;
     pub mod keccak;
+    pub mod transcript;
 }
 pub mod poly_arith {
     pub mod poly;
@@ -13,6 +14,7 @@ pub mod poly_arith {
 }
 pub mod comm {
     pub mod commitment;
+    pub mod message;
     pub mod opening;
 }
 pub mod add {
@@ -20,6 +22,8 @@ pub mod add {
     pub mod product;
 }
 pub mod addition;
+pub mod equality;
+pub mod shuffle;
 
 use poly_arith::consts::{MONT, QDATA, _8XDIV, _8XQ, _8XQINV, _PMASK, _ZETAS, _ZETAS_QINV};
 use params::Q;
@@ -5,8 +5,96 @@ pub const Q: i32 = 1073479681;
 pub const GAMMA1: i32 = 1<<18;
 pub const GAMMA2: i32 = (Q-1)/(1<<13);
 pub const D: usize = 14;
-pub const BETA: usize = 32;
+pub const T1_BITS: usize = 30 - D;
+pub const T1_MAX: i32 = ((Q-1)/2 + (1 << (D-1)) - 1) >> D;
 pub const R: usize = 4;
 pub const K: usize = 10;
 pub const L: usize = 10;
 pub const SYMBYTES: usize = 32;
+
+/*
+ * Prehash maps 2-bit chunks of an N/4-byte hash through [0, 0, 1, -1], so the
+ * weight of every c_i is random. FixedWeight(tau) gives every c_i exactly tau
+ * signs on its N/R slots, tau = 22 maximises binom(32, tau)*2^tau at about 2^48.
+ * CHALLENGE_WEIGHT bounds ||c_i||_1, so ||c_i r||_inf <= BETA whenever
+ * ||r||_inf <= RND_NORM, the bound of fresh trinary randomness.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeDist {
+    Prehash,
+    FixedWeight(usize),
+}
+
+pub const TAU: usize = 22;
+pub const CHALLENGE_DIST: ChallengeDist = ChallengeDist::FixedWeight(TAU);
+pub const CHALLENGE_WEIGHT: usize = match CHALLENGE_DIST {
+    ChallengeDist::Prehash => N/R,
+    ChallengeDist::FixedWeight(tau) => tau,
+};
+pub const RND_NORM: usize = 1;
+pub const BETA: usize = CHALLENGE_WEIGHT*RND_NORM;
+const _: () = assert!(TAU <= N/R && CHALLENGE_WEIGHT <= N/R);
+const _: () = assert!(BETA < GAMMA2 as usize && BETA < GAMMA1 as usize);
+
+/*
+ * Written out from the constants, so two parameter sets never share key ids
+ * or transcripts, e.g. "irelzk N=128 ... CHALLENGE=fixed-weight TAU=22 BETA=22".
+ */
+pub const PARAMS_ID: &[u8] = PARAMS_ID_BUF.0.split_at(PARAMS_ID_BUF.1).0;
+
+const PARAMS_ID_BUF: ([u8; 256], usize) = params_id();
+
+const fn push(buf: &mut [u8; 256], len: &mut usize, s: &[u8]) {
+    let mut i = 0;
+    while i < s.len() {
+        buf[*len] = s[i];
+        *len += 1;
+        i += 1;
+    }
+}
+
+const fn push_num(buf: &mut [u8; 256], len: &mut usize, name: &[u8], x: u64) {
+    push(buf, len, b" ");
+    push(buf, len, name);
+    push(buf, len, b"=");
+    let mut digits = [0u8; 20];
+    let (mut n, mut x) = (0, x);
+    loop {
+        digits[n] = b'0' + (x % 10) as u8;
+        n += 1;
+        x /= 10;
+        if x == 0 {
+            break;
+        }
+    }
+    while n > 0 {
+        n -= 1;
+        buf[*len] = digits[n];
+        *len += 1;
+    }
+}
+
+const fn params_id() -> ([u8; 256], usize) {
+    let mut buf = [0u8; 256];
+    let mut len = 0;
+    push(&mut buf, &mut len, b"irelzk");
+    push_num(&mut buf, &mut len, b"N", N as u64);
+    push_num(&mut buf, &mut len, b"Q", Q as u64);
+    push_num(&mut buf, &mut len, b"K", K as u64);
+    push_num(&mut buf, &mut len, b"L", L as u64);
+    push_num(&mut buf, &mut len, b"M", M as u64);
+    push_num(&mut buf, &mut len, b"R", R as u64);
+    push_num(&mut buf, &mut len, b"D", D as u64);
+    push_num(&mut buf, &mut len, b"GAMMA1", GAMMA1 as u64);
+    push_num(&mut buf, &mut len, b"GAMMA2", GAMMA2 as u64);
+    match CHALLENGE_DIST {
+        ChallengeDist::Prehash => push(&mut buf, &mut len, b" CHALLENGE=prehash"),
+        ChallengeDist::FixedWeight(tau) => {
+            push(&mut buf, &mut len, b" CHALLENGE=fixed-weight");
+            push_num(&mut buf, &mut len, b"TAU", tau as u64);
+        }
+    }
+    push_num(&mut buf, &mut len, b"RND_NORM", RND_NORM as u64);
+    push_num(&mut buf, &mut len, b"BETA", BETA as u64);
+    (buf, len)
+}
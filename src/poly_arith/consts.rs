@@ -2,12 +2,13 @@
 
 use std::ops::Deref;
 
-use crate::params::Q;
+use crate::params::{D, N, Q};
 
 use super::poly::Poly;
 pub const QINV: i32 = -1073479679; // q^-1 mod 2^32
 pub const MONT: u32 = 1048572; // 2^32 mod q
 pub const MONTSQ: u32 = 260045840; // 2^64 mod q
+pub const MONT2D: u32 = (((1u64 << D) * MONT as u64) % Q as u64) as u32; // 2^D in Montgomery form
 pub const DIV: i32 = -132153352; // mont^2/128 mod q
 
 pub const _8XQ: usize = 0;
@@ -260,3 +261,45 @@ pub const NTTX64: Poly = Poly {
         -175040315, -175040315, -175040315, -175040315, -175040315, -175040315, -175040315, -175040315
     ]
 };
+
+const fn bitrev7(a: usize) -> usize {
+    let mut r = 0;
+    let mut i = 0;
+    while i < 7 {
+        r |= ((a >> i) & 1) << (6 - i);
+        i += 1;
+    }
+    r
+}
+
+/* Position of NTT slot i in the coefficient array and back, see the AVX2 ntt output order */
+const fn slot_pos(i: usize) -> usize {
+    (i/32)*32 + 8*(i % 4) + (i % 32)/4
+}
+
+const fn pos_slot(i: usize) -> usize {
+    (i/32)*32 + 4*(i % 8) + (i % 32)/8
+}
+
+/*
+ * SIGMAIDX[k/2][i] is the position that sigma_k moves to position i in the
+ * NTT domain, for odd k mod 2N. Slot b holds the evaluation at zeta^(2b+1),
+ * which sigma_k takes from slot bk + (k-1)/2.
+ */
+const fn sigma_idx() -> [[u8; N]; N] {
+    let mut t = [[0u8; N]; N];
+    let mut k = 0;
+    while k < N {
+        let mut i = 0;
+        while i < N {
+            let b = bitrev7(pos_slot(i));
+            let b = (b*(2*k + 1) + k) % N;
+            t[k][i] = slot_pos(bitrev7(b)) as u8;
+            i += 1;
+        }
+        k += 1;
+    }
+    t
+}
+
+pub static SIGMAIDX: [[u8; N]; N] = sigma_idx();
@@ -2,14 +2,16 @@
 use std::arch::x86_64::*;
 
 use crate::{
-    poly_arith::consts::{QDATA, QINV, REJIDX, _8XQ, _8XQINV}, 
+    poly_arith::consts::{QDATA, QINV, REJIDX, SIGMAIDX, _8XQ, _8XQINV}, 
     crypto::aes256::{Aes256Ctx, AES256CTR_BLOCKBYTES}, 
     poly_arith::ntt::*, 
-    params::{N, Q, SYMBYTES}, 
+    params::{GAMMA1, N, Q, SYMBYTES, T1_BITS, T1_MAX}, 
     poly_arith::rounding::*,
 };
 
 use bytemuck::{Pod, Zeroable};
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroize;
 
 
 #[repr(C, align(32))]
@@ -21,13 +23,217 @@ pub struct Poly{
 unsafe impl Zeroable for Poly {}
 unsafe impl Pod for Poly {}
 
+impl Zeroize for Poly {
+    fn zeroize(&mut self) {
+        self.coeffs.zeroize();
+    }
+}
+
 #[repr(align(32))]
 pub struct AlignedBuf<const S: usize>([u8; S]);
 
+#[repr(align(32))]
+struct AlignedCoeffs<const S: usize>([i32; S]);
+
+impl<const S: usize> Drop for AlignedBuf<S> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 pub const REJ_UNIFORM_BUFLEN: usize = ((512+AES256CTR_BLOCKBYTES - 1)/AES256CTR_BLOCKBYTES)*AES256CTR_BLOCKBYTES;
 pub const POLY_UNIFORM_NBLOCKS: usize = (512+AES256CTR_BLOCKBYTES - 1)/AES256CTR_BLOCKBYTES;
 pub const POLY_UNIFORM_GAMMA_NBLOCKS: usize = (304+AES256CTR_BLOCKBYTES - 1)/AES256CTR_BLOCKBYTES;
 
+pub const CBD_MAX_ETA: usize = 16;
+const CBD_BUFLEN: usize = CBD_MAX_ETA*N/4;
+pub const GAUSSIAN_TAIL: f64 = 13.0;
+pub const GAUSSIAN_MAX_SIGMA: f64 = 1024.0;
+const GAUSSIAN_BUFLEN: usize = 8*N;
+
+/*
+ * T[k] = 2^63 * P(|x| <= k) for x from D_sigma, so |x| is the number of
+ * entries a uniform 63-bit value is not below; the last entry is cut off
+ */
+fn gaussian_cdt(sigma: f64) -> Vec<u64> {
+    let tail = (GAUSSIAN_TAIL*sigma).ceil() as usize;
+    let rho: Vec<f64> = (0..=tail).map(|k| (-((k*k) as f64)/(2.0*sigma*sigma)).exp()).collect();
+    let total = rho[0] + 2.0*rho[1..].iter().sum::<f64>();
+    let mut cdt = Vec::with_capacity(tail);
+    let mut acc = 0.0;
+    for (k, p) in rho[..tail].iter().enumerate() {
+        acc += if k == 0 { *p } else { 2.0*p };
+        cdt.push(((acc/total)*(1u64 << 63) as f64).min(((1u64 << 63) - 1) as f64) as u64);
+    }
+    cdt
+}
+
+/* A subgroup of the Galois group (Z/2N)^* of X^N + 1 */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subgroup {
+    elems: Vec<usize>,
+}
+
+impl Subgroup {
+    pub fn generated_by(gens: &[usize]) -> Self {
+        assert!(gens.iter().all(|&k| k % 2 == 1), "automorphisms need odd exponents");
+        let mut elems = vec![1];
+        let mut i = 0;
+        while i < elems.len() {
+            for &g in gens {
+                let k = elems[i]*g % (2*N);
+                if !elems.contains(&k) {
+                    elems.push(k);
+                }
+            }
+            i += 1;
+        }
+        elems.sort_unstable();
+        Self { elems }
+    }
+
+    pub fn elements(&self) -> &[usize] {
+        &self.elems
+    }
+
+    pub fn order(&self) -> usize {
+        self.elems.len()
+    }
+}
+
+pub const POLYUNIFORM_PACKEDBYTES: usize = N*30/8;
+pub const POLYZ_PACKEDBYTES: usize = N*19/8;
+pub const POLYT1_PACKEDBYTES: usize = N*T1_BITS/8;
+const _: () = assert!(T1_BITS == 16);
+pub const POLYHINT_PACKEDBYTES: usize = N/8;
+pub const POLYC_PACKEDBYTES: usize = N/4;
+const POLYZ_BUFLEN: usize = POLY_UNIFORM_GAMMA_NBLOCKS*AES256CTR_BLOCKBYTES;
+
+/* 19-bit little endian values minus GAMMA1, reads up to 13 bytes past the end */
+fn polyz_unpack_avx(r: &mut Poly, buf: &[u8; POLYZ_BUFLEN]) {
+    unsafe {
+        let mask  = _mm256_set1_epi32(0x7FFFF);
+        let min   = _mm256_set1_epi32(-GAMMA1);
+        let idx32 = _mm256_set_epi32(5,2,7,4,1,6,3,0);
+        let idx8  = _mm256_set_epi8(-1,10, 9, 8,-1, 8, 7, 6,
+                                     6, 5, 4, 3,-1, 3, 2, 1,
+                                    -1, 9, 8, 7, 7, 6, 5, 4,
+                                    -1, 4, 3, 2,-1, 2, 1, 0);
+        let buf_ptr = buf.as_ptr();
+        let r_ptr = r.coeffs.as_mut_ptr();
+
+        for i in 0..(N/8) {
+            let mut f = _mm256_loadu_si256(buf_ptr.add(19*i) as *const __m256i);
+            f = _mm256_permute4x64_epi64(f, 0x94);
+            f = _mm256_shuffle_epi8(f, idx8);
+            f = _mm256_srlv_epi32(f, idx32);
+            f = _mm256_and_si256(f, mask);
+            f = _mm256_add_epi32(f, min);
+            _mm256_store_si256(r_ptr.add(8*i) as *mut __m256i, f);
+        }
+    }
+}
+
+/* Two 15-byte groups of four 30-bit values per iteration, so the last store runs 2 bytes past the end */
+const POLYUNIFORM_BUFLEN: usize = POLYUNIFORM_PACKEDBYTES + 2;
+
+fn polyuniform_pack_avx(r: &mut [u8; POLYUNIFORM_BUFLEN], a: &Poly) {
+    unsafe {
+        let lo30 = _mm256_set1_epi64x((1 << 30) - 1);
+        let hi30 = _mm256_set1_epi64x(((1 << 30) - 1) << 30);
+        let idx8 = _mm256_set_epi8(-1, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+                                   -1, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+        let a_ptr = a.coeffs.as_ptr();
+        let r_ptr = r.as_mut_ptr();
+
+        for i in 0..(N/8) {
+            let f = _mm256_load_si256(a_ptr.add(8*i) as *const __m256i);
+            /* 60 bits v_2k + v_2k+1 2^30 in every 64-bit lane */
+            let w = _mm256_or_si256(_mm256_and_si256(f, lo30), _mm256_and_si256(_mm256_srli_epi64(f, 2), hi30));
+            /* 120 bits in every 128-bit lane */
+            let b = _mm256_shuffle_epi32(w, 0xEE);
+            let lo = _mm256_or_si256(w, _mm256_slli_epi64(b, 60));
+            let hi = _mm256_srli_epi64(b, 4);
+            let g = _mm256_shuffle_epi8(_mm256_blend_epi32(lo, hi, 0xCC), idx8);
+            _mm_storeu_si128(r_ptr.add(30*i) as *mut __m128i, _mm256_castsi256_si128(g));
+            _mm_storeu_si128(r_ptr.add(30*i + 15) as *mut __m128i, _mm256_extracti128_si256(g, 1));
+        }
+    }
+}
+
+fn polyuniform_unpack_avx(r: &mut Poly, a: &[u8; POLYUNIFORM_BUFLEN]) {
+    unsafe {
+        let mask = _mm256_set1_epi32((1 << 30) - 1);
+        let lo32 = _mm256_set1_epi64x(0xFFFFFFFF);
+        let idxe = _mm256_set_epi8(14, 13, 12, 11, 10, 9, 8, 7, 7, 6, 5, 4, 3, 2, 1, 0,
+                                   14, 13, 12, 11, 10, 9, 8, 7, 7, 6, 5, 4, 3, 2, 1, 0);
+        let idxo = _mm256_set_epi8(-1, -1, -1, -1, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3,
+                                   -1, -1, -1, -1, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3);
+        let shifte = _mm256_set_epi64x(4, 0, 4, 0);
+        let shifto = _mm256_set_epi64x(2, 6, 2, 6);
+        let a_ptr = a.as_ptr();
+        let r_ptr = r.coeffs.as_mut_ptr();
+
+        for i in 0..(N/8) {
+            let f = _mm256_set_m128i(_mm_loadu_si128(a_ptr.add(30*i + 15) as *const __m128i),
+                                     _mm_loadu_si128(a_ptr.add(30*i) as *const __m128i));
+            /* values 0, 2 at bit offsets 0, 60 and values 1, 3 at 30, 90 of every 120 */
+            let e = _mm256_srlv_epi64(_mm256_shuffle_epi8(f, idxe), shifte);
+            let o = _mm256_srlv_epi64(_mm256_shuffle_epi8(f, idxo), shifto);
+            let g = _mm256_or_si256(_mm256_and_si256(e, lo32), _mm256_slli_epi64(o, 32));
+            _mm256_store_si256(r_ptr.add(8*i) as *mut __m256i, _mm256_and_si256(g, mask));
+        }
+    }
+}
+
+/* 16-bit two's complement, the signed saturation of packs is exact on [-2^15, 2^15) */
+fn polyt1_pack_avx(r: &mut [u8], a: &Poly) {
+    unsafe {
+        let a_ptr = a.coeffs.as_ptr();
+        let r_ptr = r.as_mut_ptr();
+        for i in 0..(N/16) {
+            let f = _mm256_load_si256(a_ptr.add(16*i) as *const __m256i);
+            let g = _mm256_load_si256(a_ptr.add(16*i + 8) as *const __m256i);
+            let h = _mm256_permute4x64_epi64(_mm256_packs_epi32(f, g), 0xD8);
+            _mm256_storeu_si256(r_ptr.add(32*i) as *mut __m256i, h);
+        }
+    }
+}
+
+fn polyt1_unpack_avx(r: &mut Poly, a: &[u8]) {
+    unsafe {
+        let a_ptr = a.as_ptr();
+        let r_ptr = r.coeffs.as_mut_ptr();
+        for i in 0..(N/8) {
+            let f = _mm256_cvtepi16_epi32(_mm_loadu_si128(a_ptr.add(16*i) as *const __m128i));
+            _mm256_store_si256(r_ptr.add(8*i) as *mut __m256i, f);
+        }
+    }
+}
+
+/* true if some coefficient lies outside [lo, hi] */
+fn out_of_range_avx(a: &Poly, lo: i32, hi: i32) -> bool {
+    let a_ptr = a.coeffs.as_ptr();
+    unsafe {
+        let lo = _mm256_set1_epi32(lo);
+        let hi = _mm256_set1_epi32(hi);
+        let mut t = _mm256_setzero_si256();
+        for i in 0..(N/8) {
+            let f = _mm256_load_si256(a_ptr.add(8*i) as *const __m256i);
+            t = _mm256_or_si256(t, _mm256_cmpgt_epi32(lo, f));
+            t = _mm256_or_si256(t, _mm256_cmpgt_epi32(f, hi));
+        }
+        _mm256_testz_si256(t, t) == 0
+    }
+}
+
+/* Sign extends the low `bits` bits of every coefficient */
+fn sign_extend(a: &mut Poly, bits: usize) {
+    for x in a.coeffs.iter_mut() {
+        *x = (*x << (32 - bits)) >> (32 - bits);
+    }
+}
+
 impl Poly {
     pub fn new() -> Self {
         Self {
@@ -180,16 +386,21 @@ impl Poly {
         ctr
     }
 
-    pub fn uniform_preinit(r: &mut Poly, state: &mut Aes256Ctx) {
+    /*
+     * The *_preinit samplers draw whole AES blocks through fill_bytes, which
+     * Aes256Ctx encrypts straight into the buffer once its own keystream
+     * buffer is drained, so any other seeded RngCore can drive them as well.
+     */
+    pub fn uniform_preinit<G: RngCore + CryptoRng>(r: &mut Poly, state: &mut G) {
         let mut ctr = 0;
         const BUFSIZE: usize = POLY_UNIFORM_NBLOCKS * AES256CTR_BLOCKBYTES;
         let mut buf = AlignedBuf::<BUFSIZE>([0u8; BUFSIZE]);
-        state.squeezeblocks(&mut buf.0, POLY_UNIFORM_NBLOCKS);
+        state.fill_bytes(&mut buf.0);
         ctr += Self::rej_uniform_avx(&mut r.coeffs, &buf.0);
 
         while ctr < N {
-            state.squeezeblocks(&mut buf.0, 1);
-            ctr += Self::rej_uniform(&mut r.coeffs[ctr..], &buf.0)
+            state.fill_bytes(&mut buf.0[..AES256CTR_BLOCKBYTES]);
+            ctr += Self::rej_uniform(&mut r.coeffs[ctr..], &buf.0[..AES256CTR_BLOCKBYTES])
         }
     }
 
@@ -198,7 +409,7 @@ impl Poly {
         Self::uniform_preinit(r, &mut state);
     }
 
-    pub fn trinary_preinit(r: &mut Poly, state: &mut Aes256Ctx) {
+    pub fn trinary_preinit<G: RngCore + CryptoRng>(r: &mut Poly, state: &mut G) {
         const BUFSIZE: usize = N/2;
         let mut buf = AlignedBuf::<BUFSIZE>([0u8; BUFSIZE]); 
         unsafe {
@@ -208,7 +419,7 @@ impl Poly {
             let mask2 = _mm256_srli_epi32(mask32, 30);
             let r_ptr = r.coeffs.as_mut_ptr();
 
-            state.squeezeblocks(&mut buf.0, 1);
+            state.fill_bytes(&mut buf.0);
 
             let buf_ptr = buf.0.as_ptr();
             for i in 0..(N/16) {
@@ -237,41 +448,249 @@ impl Poly {
 
     }
 
-    pub fn uniform_gamma_preinit(r: &mut Poly, state: &mut Aes256Ctx) {
-        const BUFSIZE:usize = POLY_UNIFORM_GAMMA_NBLOCKS * AES256CTR_BLOCKBYTES;
-        let mut buf = AlignedBuf::<BUFSIZE>([0u8; BUFSIZE]);
-        let mut pos = 0;
-        unsafe {
-            let mask  = _mm256_set1_epi32(0x7FFFF);
-            let min   = _mm256_set1_epi32(-(1 << 18));
-            let idx32 = _mm256_set_epi32(5,2,7,4,1,6,3,0);
-            let idx8  = _mm256_set_epi8(-1,10, 9, 8,-1, 8, 7, 6,
-                                         6, 5, 4, 3,-1, 3, 2, 1,
-                                        -1, 9, 8, 7, 7, 6, 5, 4,
-                                        -1, 4, 3, 2,-1, 2, 1, 0);
-
-            state.squeezeblocks(&mut buf.0, POLY_UNIFORM_GAMMA_NBLOCKS);
-            let buf_ptr = buf.0.as_ptr();
-            let r_ptr = r.coeffs.as_mut_ptr();
+    /* Centred binomial distribution, coefficient a_1 + .. + a_eta - b_1 - .. - b_eta over random bits */
+    pub fn cbd_preinit<G: RngCore + CryptoRng>(r: &mut Poly, state: &mut G, eta: usize) {
+        assert!((1..=CBD_MAX_ETA).contains(&eta));
+        let mut buf = AlignedBuf::<CBD_BUFLEN>([0u8; CBD_BUFLEN]);
+        let nblocks = (eta*N/4).div_ceil(AES256CTR_BLOCKBYTES);
+        state.fill_bytes(&mut buf.0[..nblocks*AES256CTR_BLOCKBYTES]);
+
+        let mask = (1u64 << eta) - 1;
+        let (mut acc, mut n, mut k) = (0u64, 0, 0);
+        for x in r.coeffs.iter_mut() {
+            while n < 2*eta {
+                acc |= (buf.0[k] as u64) << n;
+                n += 8;
+                k += 1;
+            }
+            let a = (acc & mask).count_ones() as i32;
+            let b = ((acc >> eta) & mask).count_ones() as i32;
+            *x = a - b;
+            acc >>= 2*eta;
+            n -= 2*eta;
+        }
+        acc.zeroize();
+    }
 
-            for i in 0..(N/8) {
-                let mut f = _mm256_loadu_si256(buf_ptr.add(pos) as *const __m256i);    
-                pos += 19;
-                f = _mm256_permute4x64_epi64(f, 0x94);
-                f = _mm256_shuffle_epi8(f, idx8);
-                f = _mm256_srlv_epi32(f, idx32);
-                f = _mm256_and_si256(f, mask);
-                f = _mm256_add_epi32(f, min);
-                _mm256_store_si256(r_ptr.add(8*i) as *mut __m256i, f);
+    pub fn cbd(r: &mut Poly, seed: &[u8; SYMBYTES], nonce: u16, eta: usize) {
+        let mut state = Aes256Ctx::init(seed, nonce as u64);
+        Self::cbd_preinit(r, &mut state, eta);
+    }
+
+    /*
+     * Discrete Gaussian D_sigma by inversion of a cumulative distribution table
+     * cut at GAUSSIAN_TAIL*sigma. Every table entry is compared for every
+     * coefficient so the time only depends on sigma; the table is built in f64,
+     * which bounds its precision to about 2^-53.
+     */
+    pub fn gaussian_preinit<G: RngCore + CryptoRng>(r: &mut Poly, state: &mut G, sigma: f64) {
+        assert!(sigma > 0.0 && sigma <= GAUSSIAN_MAX_SIGMA);
+        let cdt = gaussian_cdt(sigma);
+        let mut buf = AlignedBuf::<GAUSSIAN_BUFLEN>([0u8; GAUSSIAN_BUFLEN]);
+        state.fill_bytes(&mut buf.0);
+
+        for (x, b) in r.coeffs.iter_mut().zip(buf.0.chunks_exact(8)) {
+            let mut u = u64::from_le_bytes(b.try_into().unwrap());
+            let sign = (u >> 63) as i32;
+            u &= (1 << 63) - 1;
+            let mut z = 0i32;
+            for &t in cdt.iter() {
+                z += (1 - (u.wrapping_sub(t) >> 63)) as i32;
             }
+            *x = (z ^ -sign) + sign;
+            u.zeroize();
         }
     }
 
+    pub fn gaussian(r: &mut Poly, seed: &[u8; SYMBYTES], nonce: u16, sigma: f64) {
+        let mut state = Aes256Ctx::init(seed, nonce as u64);
+        Self::gaussian_preinit(r, &mut state, sigma);
+    }
+
+    pub fn uniform_gamma_preinit<G: RngCore + CryptoRng>(r: &mut Poly, state: &mut G) {
+        let mut buf = AlignedBuf::<POLYZ_BUFLEN>([0u8; POLYZ_BUFLEN]);
+        state.fill_bytes(&mut buf.0);
+        polyz_unpack_avx(r, &buf.0);
+    }
+
     pub fn uniform_gamma(r: &mut Poly, seed: &[u8; SYMBYTES], nonce: u16) {
         let mut state = Aes256Ctx::init(seed, nonce as u64);
         Self::uniform_gamma_preinit(r, &mut state);
     }
     
+    /* Product in Z_q[X]/(X^N + 1) of coefficient domain inputs, centred as by freeze */
+    pub fn mul(a: &Poly, b: &Poly) -> Poly {
+        let mut r = *a;
+        let mut bhat = *b;
+        r.ntt();
+        bhat.ntt();
+        r.pointwise_montgomery(&bhat);
+        r.inverse_ntt_tomont();
+        r.reduce();
+        r.freeze();
+        r
+    }
+
+    /*
+     * c*a in Z[X]/(X^N + 1) for a public ternary c and small a in the coefficient
+     * domain, without reduction: weight(c)*max|a| must stay below 2^31. Only the
+     * positions of c are branched on. Beats the NTT round trip for sparse c.
+     */
+    pub fn mul_sparse_ternary(r: &mut Poly, c: &Poly, a: &Poly) {
+        let mut ext = AlignedCoeffs([0i32; 2*N]);
+        for i in 0..N {
+            ext.0[i] = -a.coeffs[i];
+            ext.0[N + i] = a.coeffs[i];
+        }
+        let ext_ptr = ext.0.as_ptr();
+        let r_ptr = r.coeffs.as_mut_ptr();
+        unsafe {
+            let mut acc = [_mm256_setzero_si256(); N/8];
+            for (j, &cj) in c.coeffs.iter().enumerate() {
+                if cj == 0 {
+                    continue;
+                }
+                let sign = _mm256_set1_epi32(cj);
+                for (k, acc) in acc.iter_mut().enumerate() {
+                    let f = _mm256_loadu_si256(ext_ptr.add(N - j + 8*k) as *const __m256i);
+                    *acc = _mm256_add_epi32(*acc, _mm256_sign_epi32(f, sign));
+                }
+            }
+            for (k, acc) in acc.iter().enumerate() {
+                _mm256_store_si256(r_ptr.add(8*k) as *mut __m256i, *acc);
+            }
+        }
+        ext.0.zeroize();
+    }
+
+    /* Slow reference for mul, coefficients in [0, q) */
+    pub fn mul_schoolbook(a: &Poly, b: &Poly) -> Poly {
+        let mut t = [0i64; 2*N];
+        for i in 0..N {
+            for j in 0..N {
+                t[i+j] = (t[i+j] + a.coeffs[i] as i64 * b.coeffs[j] as i64) % Q as i64;
+            }
+        }
+        let mut r = Poly::new();
+        for i in 0..N {
+            r.coeffs[i] = (t[i] - t[i+N]).rem_euclid(Q as i64) as i32;
+        }
+        r
+    }
+
+    /* Representatives in [0, q), only for public data */
+    pub fn canonical(&mut self) {
+        for x in self.coeffs.iter_mut() {
+            *x = x.rem_euclid(Q);
+        }
+    }
+
+    /* Packs the low `bits` bits of every coefficient little endian into N*bits/8 bytes */
+    pub fn pack_bits(r: &mut [u8], a: &Poly, bits: usize) {
+        assert_eq!(r.len(), N*bits/8);
+        let mask = (1u64 << bits) - 1;
+        let (mut acc, mut n, mut k) = (0u64, 0, 0);
+        for &x in a.coeffs.iter() {
+            acc |= ((x as u32 as u64) & mask) << n;
+            n += bits;
+            while n >= 8 {
+                r[k] = acc as u8;
+                acc >>= 8;
+                n -= 8;
+                k += 1;
+            }
+        }
+    }
+
+    pub fn unpack_bits(r: &mut Poly, a: &[u8], bits: usize) {
+        assert_eq!(a.len(), N*bits/8);
+        let mask = (1u64 << bits) - 1;
+        let (mut acc, mut n, mut k) = (0u64, 0, 0);
+        for x in r.coeffs.iter_mut() {
+            while n < bits {
+                acc |= (a[k] as u64) << n;
+                n += 8;
+                k += 1;
+            }
+            *x = (acc & mask) as i32;
+            acc >>= bits;
+            n -= bits;
+        }
+    }
+
+    /* Coefficients in [0, q) at 30 bits each, the same layout as pack_bits */
+    pub fn pack_uniform(r: &mut [u8], a: &Poly) {
+        assert_eq!(r.len(), POLYUNIFORM_PACKEDBYTES);
+        assert!(!out_of_range_avx(a, 0, Q-1));
+        let mut buf = AlignedBuf::<POLYUNIFORM_BUFLEN>([0u8; POLYUNIFORM_BUFLEN]);
+        polyuniform_pack_avx(&mut buf.0, a);
+        r.copy_from_slice(&buf.0[..POLYUNIFORM_PACKEDBYTES]);
+    }
+
+    /* Returns true on a coefficient outside [0, q) */
+    pub fn unpack_uniform(r: &mut Poly, a: &[u8]) -> bool {
+        assert_eq!(a.len(), POLYUNIFORM_PACKEDBYTES);
+        let mut buf = AlignedBuf::<POLYUNIFORM_BUFLEN>([0u8; POLYUNIFORM_BUFLEN]);
+        buf.0[..POLYUNIFORM_PACKEDBYTES].copy_from_slice(a);
+        polyuniform_unpack_avx(r, &buf.0);
+        out_of_range_avx(r, 0, Q-1)
+    }
+
+    /* Coefficients in [-GAMMA1, GAMMA1) as GAMMA1 + z at 19 bits each */
+    pub fn pack_z(r: &mut [u8], a: &Poly) {
+        assert!(!out_of_range_avx(a, -GAMMA1, GAMMA1-1));
+        let mut t = *a;
+        for x in t.coeffs.iter_mut() {
+            *x += GAMMA1;
+        }
+        Self::pack_bits(r, &t, 19);
+    }
+
+    /* Every 19-bit code is a value in [-GAMMA1, GAMMA1) */
+    pub fn unpack_z(r: &mut Poly, a: &[u8]) {
+        assert_eq!(a.len(), POLYZ_PACKEDBYTES);
+        let mut buf = AlignedBuf::<POLYZ_BUFLEN>([0u8; POLYZ_BUFLEN]);
+        buf.0[..POLYZ_PACKEDBYTES].copy_from_slice(a);
+        polyz_unpack_avx(r, &buf.0);
+    }
+
+    /* Centred power2round high bits in [-T1_MAX, T1_MAX], two's complement */
+    pub fn pack_t1(r: &mut [u8], a: &Poly) {
+        assert_eq!(r.len(), POLYT1_PACKEDBYTES);
+        assert!(!out_of_range_avx(a, -T1_MAX, T1_MAX));
+        polyt1_pack_avx(r, a);
+    }
+
+    /* Returns true on a coefficient outside [-T1_MAX, T1_MAX] */
+    pub fn unpack_t1(r: &mut Poly, a: &[u8]) -> bool {
+        assert_eq!(a.len(), POLYT1_PACKEDBYTES);
+        polyt1_unpack_avx(r, a);
+        out_of_range_avx(r, -T1_MAX, T1_MAX)
+    }
+
+    /* Hints are 0 or 1, one bit each */
+    pub fn pack_hint(r: &mut [u8], a: &Poly) {
+        assert!(!out_of_range_avx(a, 0, 1));
+        Self::pack_bits(r, a, 1);
+    }
+
+    pub fn unpack_hint(r: &mut Poly, a: &[u8]) {
+        Self::unpack_bits(r, a, 1);
+    }
+
+    /* Ternary coefficients at 2 bits each, -1 encoded as 3 */
+    pub fn pack_challenge(r: &mut [u8], a: &Poly) {
+        assert!(!out_of_range_avx(a, -1, 1));
+        Self::pack_bits(r, a, 2);
+    }
+
+    /* Returns true on the unused code 2 */
+    pub fn unpack_challenge(r: &mut Poly, a: &[u8]) -> bool {
+        Self::unpack_bits(r, a, 2);
+        sign_extend(r, 2);
+        out_of_range_avx(r, -1, 1)
+    }
+
     pub fn freeze(&mut self) {
         unsafe {
             let qdata_ptr = QDATA.0.as_ptr();
@@ -427,6 +846,26 @@ impl Poly {
         }
     }
     
+    /* Infinity norm with coefficients in (-q, q) read as centred representatives */
+    pub fn inf_norm(a: &Poly) -> u32 {
+        let qdata_ptr = QDATA.0.as_ptr();
+        let a_ptr = a.coeffs.as_ptr();
+        let mut out = [0u32; 8];
+        unsafe {
+            let q = _mm256_load_si256(qdata_ptr.add(_8XQ) as *const __m256i);
+            let hq = _mm256_srli_epi32(q, 1);
+            let mut t = _mm256_setzero_si256();
+            for i in 0..(N/8) {
+                let mut f = _mm256_load_si256(a_ptr.add(8*i) as *const __m256i);
+                let g = _mm256_and_si256(_mm256_cmpgt_epi32(f, hq), q);
+                f = _mm256_abs_epi32(_mm256_sub_epi32(f, g));
+                t = _mm256_max_epu32(t, f);
+            }
+            _mm256_storeu_si256(out.as_mut_ptr() as *mut __m256i, t);
+        }
+        out.iter().fold(0, |m, &x| m.max(x))
+    }
+
     pub fn sigma(a: &Poly, k: isize) -> Poly {
         let mut t = Poly::new();
         let mut j: usize = 0; 
@@ -442,6 +881,28 @@ impl Poly {
         t
     } 
     
+    /* The automorphism X -> X^k for any odd k in the NTT domain, a permutation of the slots */
+    pub fn sigma_ntt(&mut self, k: usize) {
+        let a = *self;
+        Self::sigma_ntt_other(self, &a, k);
+    }
+
+    pub fn sigma_ntt_other(r: &mut Poly, a: &Poly, k: usize) {
+        assert!(k % 2 == 1, "sigma_k needs an odd k");
+        let idx = &SIGMAIDX[(k % (2*N))/2];
+        let idx_ptr = idx.as_ptr();
+        let a_ptr = a.coeffs.as_ptr();
+        let r_ptr = r.coeffs.as_mut_ptr();
+        unsafe {
+            for i in 0..(N/8) {
+                let g = _mm_loadl_epi64(idx_ptr.add(8*i) as *const __m128i);
+                let g = _mm256_cvtepu8_epi32(g);
+                let f = _mm256_i32gather_epi32(a_ptr, g, 4);
+                _mm256_store_si256(r_ptr.add(8*i) as *mut __m256i, f);
+            }
+        }
+    }
+
     pub fn sigma65_ntt (&mut self) {
         let coeffs_ptr = self.coeffs.as_mut_ptr();
         unsafe {
@@ -587,6 +1048,30 @@ impl Poly {
         }
     }
     
+    /* Sum of sigma_k(a) over the subgroup, lands in the subring fixed by it */
+    pub fn trace_ntt(&mut self, h: &Subgroup) {
+        let a = *self;
+        Self::trace_ntt_other(self, &a, h);
+    }
+
+    pub fn trace_ntt_other(r: &mut Poly, a: &Poly, h: &Subgroup) {
+        *r = Poly::new();
+        let mut tmp = Poly::new();
+        for &k in h.elements() {
+            Self::sigma_ntt_other(&mut tmp, a, k);
+            r.add(&tmp);
+        }
+    }
+
+    /* Slow coefficient domain reference for trace_ntt */
+    pub fn trace(a: &Poly, h: &Subgroup) -> Poly {
+        let mut r = Poly::new();
+        for &k in h.elements() {
+            r.add(&Self::sigma(a, k as isize));
+        }
+        r
+    }
+
     pub fn trace65_ntt(&mut self) {
         let coeffs_ptr = self.coeffs.as_mut_ptr();
         unsafe {
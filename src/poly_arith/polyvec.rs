@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 use bytemuck::{Pod, Zeroable};
+use zeroize::Zeroize;
 
-use crate::poly_arith::poly::Poly;
-use crate::params::{K, L, M};
+use crate::poly_arith::{consts::{MONT, MONTSQ}, poly::Poly};
+use crate::params::{K, L, M, Q};
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -13,6 +14,14 @@ pub struct PolyVec<const S: usize> {
 unsafe impl<const S: usize> Zeroable for PolyVec<S> {}
 unsafe impl<const S: usize> Pod for PolyVec<S> {}
 
+impl<const S: usize> Zeroize for PolyVec<S> {
+    fn zeroize(&mut self) {
+        for i in 0..S {
+            self.vec[i].zeroize();
+        }
+    }
+}
+
 impl<const S: usize> PolyVec<S> {
     pub fn new() -> Self {
         Self {
@@ -97,6 +106,21 @@ impl<const S: usize> PolyVec<S> {
         }
     }
 
+    /* Multiplication of an NTT domain vector by a small public polynomial c */
+    pub fn mul_poly(&mut self, c: &Poly) {
+        let mut chat = *c;
+        chat.ntt();
+        chat.scale_montgomery(MONTSQ as i32);
+        for i in 0..S {
+            self.vec[i].pointwise_montgomery(&chat);
+        }
+    }
+
+    pub fn mul_scalar(&mut self, c: i32) {
+        let s = ((c as i64) * (MONT as i64)) % (Q as i64);
+        self.scale_montgomery(s as i32);
+    }
+
     pub fn vec_check_norm(v: &PolyVec<S>, b: u32) -> bool {
         for i in 0..S {
             if Poly::check_norm(&v.vec[i], b) {
@@ -106,6 +130,10 @@ impl<const S: usize> PolyVec<S> {
         return false;
     }
 
+    pub fn vec_inf_norm(v: &PolyVec<S>) -> u32 {
+        v.vec.iter().fold(0, |m, p| m.max(Poly::inf_norm(p)))
+    }
+
     pub fn vec_power2round_other (v1: &mut PolyVec<S>, v0: &mut PolyVec<S>, v: &mut PolyVec<S>) {
         for i in 0..S {
             Poly::power2round_other(&mut v1.vec[i], &mut v0.vec[i], &mut v.vec[i]);
@@ -0,0 +1,453 @@
+#![allow(dead_code)]
+
+use rand::{rngs::OsRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::{
+    add::product::{autobase_proof, autobase_verify, poly_shift, poly_sigmainv_ntt},
+    comm::{
+        commitment::{Comm, CommKey, CommRnd, CompressedComm, ExpandedComm},
+        opening
+    },
+    crypto::{shake::XofReader, transcript::{self, Transcript}},
+    equality::Witness,
+    params::{K, L, M, N, Q, R, SYMBYTES},
+    poly_arith::{
+        consts::{MONT, MONTSQ, NTTX, NTTX2, NTTX3},
+        poly::Poly,
+        polyvec::{PolyVecK, PolyVecL, PolyVecM}
+    }
+};
+
+const DOMAIN: &[u8] = b"irelzk-rs shuffle proof";
+
+/*
+ * The N NTT slots of b are a permutation of those of a iff
+ * sum 1/(x - a_i) = sum 1/(x - b_i) for a random x. Otherwise the
+ * difference is a nonzero rational function of degree at most 2N - 2 in x,
+ * so one x misses with probability below 2^-22 and XS of them, all derived
+ * from the statement, bring this below 2^-128.
+ * Helper commitment k holds u = 1/(x - a) and v = 1/(x - b) for x_2k in
+ * slots 0 and 1 and for x_2k+1 in slots 2 and 3, the product proof
+ * garbage in slot M-2 and the mask of the linear proof in slot M-1.
+ * The product proof shows u(x - a) = 1 and v(x - b) = 1 slot-wise as in
+ * add::product, the linear proof shows sum u - v = 0 for both x through
+ * the trace as in add::linear, at coefficients 0 and 1 of h_k.
+ */
+const XS: usize = 6;
+const HELPERS: usize = XS/2;
+const _: () = assert!(XS == 2*HELPERS && 4 <= M-2);
+
+pub struct ShuffleProof {
+    th: Vec<CompressedComm>,
+    h: [Poly; HELPERS],
+    chash: [u8; N/4],
+    za: [CommRnd; R],
+    zb: [CommRnd; R],
+    zh: Vec<[CommRnd; R]>,
+}
+
+fn inverse(a: i32) -> i32 {
+    let (mut r, mut b, mut e) = (1i64, a as i64, Q as i64 - 2);
+    while e > 0 {
+        if e & 1 == 1 {
+            r = r * b % Q as i64;
+        }
+        b = b * b % Q as i64;
+        e >>= 1;
+    }
+    r as i32
+}
+
+fn sample_x(xof: &mut XofReader) -> i32 {
+    loop {
+        let x = (xof.read_u32_le() & ((1 << 30) - 1)) as i32;
+        if x < Q {
+            return x;
+        }
+    }
+}
+
+fn challenge_full(chat: &[Poly; R]) -> Poly {
+    let mut tmp = Poly::new();
+    let mut cfull = chat[0];
+    Poly::pointwise_montgomery_other(&mut tmp, &chat[1], &NTTX);
+    cfull.add(&tmp);
+    Poly::pointwise_montgomery_other(&mut tmp, &chat[2], &NTTX2);
+    cfull.add(&tmp);
+    Poly::pointwise_montgomery_other(&mut tmp, &chat[3], &NTTX3);
+    cfull.add(&tmp);
+    cfull.reduce();
+    cfull
+}
+
+fn scale(a: &Poly, x: i32) -> Poly {
+    let mut r = Poly::new();
+    Poly::scale_montgomery_other(&mut r, a, ((x as i64 * MONT as i64) % Q as i64) as i32);
+    r
+}
+
+fn canonical(mut a: Poly) -> Poly {
+    a.reduce();
+    a.canonical();
+    a
+}
+
+/* helper slot s in 0..4 relates to the message of a for even s, of b for odd s, at x_2k + s/2 */
+fn helper_x(x: &[i32; XS], k: usize, s: usize) -> i32 {
+    x[2*k + s/2]
+}
+
+/* sum over all slots of the helper slots 2i - (2i + 1), at coefficient i through the trace */
+fn trace_sums(m: &PolyVecM) -> Poly {
+    let mut r = Poly::new();
+    for i in 0..2 {
+        let mut d = m.vec[2*i];
+        d.sub(&m.vec[2*i + 1]);
+        d.reduce();
+        d.trace65_ntt();
+        let mut tmp = Poly::new();
+        poly_shift(&mut tmp, &d, i);
+        r.add(&tmp);
+        r.reduce();
+    }
+    r
+}
+
+impl ShuffleProof {
+    /*
+     * Both messages must be in the NTT domain, their slots are what gets permuted.
+     * Returns None if some x hits a slot value, which happens with probability
+     * about XS*2N/q = 2^-19.4; commit again with fresh randomness in that case.
+     * Also None if the randomness of either commitment is above opening::MAX_RND_NORM.
+     */
+    pub fn prove(a: &Witness, b: &Witness) -> Option<Self> {
+        assert!(a.slot < M && b.slot < M);
+        let (mut sa, mut sb) = (canonical(a.msg.vec[a.slot]).coeffs, canonical(b.msg.vec[b.slot]).coeffs);
+        sa.sort_unstable();
+        sb.sort_unstable();
+        assert_eq!(sa, sb, "the message slots are not a permutation of each other");
+        Self::prove_unchecked(a, b)
+    }
+
+    /* prove without the permutation check, only to test that the verifier catches a false statement */
+    #[doc(hidden)]
+    pub fn prove_unchecked(a: &Witness, b: &Witness) -> Option<Self> {
+        assert!(a.slot < M && b.slot < M);
+        assert!(!a.t.verify_opening(a.ck, a.r, a.msg, a.r.norm), "invalid opening of the first commitment");
+        assert!(!b.t.verify_opening(b.ck, b.r, b.msg, b.r.norm), "invalid opening of the second commitment");
+        if a.r.norm > opening::MAX_RND_NORM || b.r.norm > opening::MAX_RND_NORM {
+            return None;
+        }
+        let ma = canonical(a.msg.vec[a.slot]);
+        let mb = canonical(b.msg.vec[b.slot]);
+
+        let (tca, t0lowa) = a.t.power2round();
+        let (tcb, t0lowb) = b.t.power2round();
+        let (ta, tb) = (tca.expand(), tcb.expand());
+        let mut statement = Self::statement(a.ck, &ta, a.slot, b.ck, &tb, b.slot);
+        let x = Self::challenge_x(&statement);
+        if x.iter().any(|x| ma.coeffs.contains(x) || mb.coeffs.contains(x)) {
+            return None;
+        }
+
+        let mut nonce = 0;
+        let mut seed  = Zeroizing::new([0u8; SYMBYTES]);
+        OsRng.fill_bytes(seed.as_mut());
+
+        let mut mh = Zeroizing::new([PolyVecM::new(); HELPERS]);
+        for (k, m) in mh.iter_mut().enumerate() {
+            for s in 0..4 {
+                let mp = if s % 2 == 0 { &ma } else { &mb };
+                for i in 0..N {
+                    m.vec[s].coeffs[i] = inverse((helper_x(&x, k, s) - mp.coeffs[i]).rem_euclid(Q));
+                }
+            }
+            Poly::uniform_random(&mut m.vec[M-1], &seed, nonce);
+            nonce += 1;
+            for i in 0..R {
+                m.vec[M-1].coeffs[i] = 0;
+            }
+            m.vec[M-1].ntt();
+        }
+
+        let rh: [CommRnd; HELPERS] = std::array::from_fn(|_| CommRnd::generate());
+        let (mut tch, t0lowh): (Vec<CompressedComm>, Vec<PolyVecK>) =
+            (0..HELPERS).map(|k| Comm::commit(a.ck, &rh[k], &mh[k]).power2round()).unzip();
+        let th: [ExpandedComm; HELPERS] = std::array::from_fn(|k| tch[k].expand());
+        Self::append_helpers(&mut statement, &th);
+
+        let (rhata, rhatb) = (a.r.to_ntt(), b.r.to_ntt());
+        let rhath: [CommRnd; HELPERS] = std::array::from_fn(|k| rh[k].to_ntt());
+        let mut za: [CommRnd; R] = std::array::from_fn(|_| CommRnd::new());
+        let mut zb: [CommRnd; R] = std::array::from_fn(|_| CommRnd::new());
+        let mut zh: Vec<[CommRnd; R]> = (0..HELPERS).map(|_| std::array::from_fn(|_| CommRnd::new())).collect();
+        loop {
+            opening::reseed(&mut seed, &mut nonce, 2 + HELPERS);
+            let mut w1a: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+            let mut w1b: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+            let mut w1h: Vec<[PolyVecK; R]> = (0..HELPERS).map(|_| std::array::from_fn(|_| PolyVecK::new())).collect();
+            let mut  ga: [PolyVecM; R] = std::array::from_fn(|_| PolyVecM::new());
+            let mut  gb: [PolyVecM; R] = std::array::from_fn(|_| PolyVecM::new());
+            let mut  gh: Vec<[PolyVecM; R]> = (0..HELPERS).map(|_| std::array::from_fn(|_| PolyVecM::new())).collect();
+            let mut  ya = opening::generate_y(&seed, nonce);
+            nonce += (R*(K+L+M)) as u16;
+            let mut  yb = opening::generate_y(&seed, nonce);
+            nonce += (R*(K+L+M)) as u16;
+            let mut  yh: Vec<[CommRnd; R]> = (0..HELPERS).map(|_| {
+                let y = opening::generate_y(&seed, nonce);
+                nonce += (R*(K+L+M)) as u16;
+                y
+            }).collect();
+            opening::first(&mut w1a, &mut ga, &mut ya, a.ck);
+            opening::first(&mut w1b, &mut gb, &mut yb, b.ck);
+            for k in 0..HELPERS {
+                opening::first(&mut w1h[k], &mut gh[k], &mut yh[k], a.ck);
+            }
+
+            let mut transcript = statement.clone();
+            transcript.append_polyvecs(b"w1-a", &w1a);
+            transcript.append_polyvecs(b"w1-b", &w1b);
+            for w1 in w1h.iter() {
+                transcript.append_polyvecs(b"w1-h", w1);
+            }
+            let mut xof = transcript.challenge_xof(b"product");
+            let alpha: [[Poly; 4]; HELPERS] = std::array::from_fn(|_| std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof)));
+            let beta : [[Poly; R]; HELPERS] = std::array::from_fn(|_| std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof)));
+
+            let mut apa = [Poly::new(); R];
+            let mut apb = [Poly::new(); R];
+            autobase_proof(&mut apa, &ga, a.slot);
+            autobase_proof(&mut apb, &gb, b.slot);
+
+            let mut tmgarbage = [Poly::new(); HELPERS];
+            let mut v = [Poly::new(); HELPERS];
+            let mut h = [Poly::new(); HELPERS];
+            let mut vpr = [[Poly::new(); R]; HELPERS];
+            for k in 0..HELPERS {
+                let mut garbage = Poly::new();
+                for (s, alpha) in alpha[k].iter().enumerate() {
+                    let (ap, mp) = if s % 2 == 0 { (&apa, &ma) } else { (&apb, &mb) };
+                    let mut au = [Poly::new(); R];
+                    autobase_proof(&mut au, &gh[k], s);
+                    for j in 0..R {
+                        let mut tmp = Poly::new();
+                        Poly::pointwise_montgomery_other(&mut tmp, &au[j], &ap[j]);
+                        tmp.pointwise_montgomery(alpha);
+                        tmp.pointwise_montgomery(&beta[k][j]);
+                        v[k].add(&tmp);
+                        v[k].reduce();
+
+                        /* the coefficient of c in (a_u - c u)(a_p - c m) + c(x a_u - c(x u - 1)) */
+                        let mut mprime = Poly::new();
+                        let mut cross = Poly::new();
+                        poly_sigmainv_ntt(&mut mprime, mp, j);
+                        Poly::pointwise_montgomery_other(&mut cross, &au[j], &mprime);
+                        poly_sigmainv_ntt(&mut mprime, &mh[k].vec[s], j);
+                        Poly::pointwise_montgomery_other(&mut tmp, &ap[j], &mprime);
+                        cross.add(&tmp);
+                        cross.reduce();
+                        Poly::scale_montgomery_other(&mut tmp, &au[j], helper_x(&x, k, s));
+                        cross.sub(&tmp);
+                        cross.reduce();
+                        cross.pointwise_montgomery(alpha);
+                        cross.pointwise_montgomery(&beta[k][j]);
+                        garbage.sub(&cross);
+                        garbage.reduce();
+                    }
+                }
+                garbage.scale_montgomery(MONTSQ as i32);
+                v[k].scale_montgomery(MONTSQ as i32);
+                for (j, g) in gh[k].iter().enumerate() {
+                    let mut tmp = Poly::new();
+                    poly_shift(&mut tmp, &g.vec[M-2], j);
+                    v[k].add(&tmp);
+                    v[k].reduce();
+                }
+                v[k] = canonical(v[k]);
+                tmgarbage[k] = th[k].tm.vec[M-2];
+                tmgarbage[k].add(&garbage);
+                tmgarbage[k] = canonical(tmgarbage[k]);
+
+                h[k] = mh[k].vec[M-1];
+                h[k].add(&trace_sums(&mh[k]));
+                h[k].inverse_ntt();
+                h[k] = canonical(h[k]);
+                vpr[k] = std::array::from_fn(|j| {
+                    let mut d = trace_sums(&gh[k][j]);
+                    d.add(&gh[k][j].vec[M-1]);
+                    canonical(d)
+                });
+            }
+
+            for k in 0..HELPERS {
+                transcript.append_poly(b"tm-garbage", &tmgarbage[k]);
+                transcript.append_poly(b"v", &v[k]);
+                transcript.append_poly(b"h", &h[k]);
+                transcript.append_polys(b"vprime", &vpr[k]);
+            }
+            let mut xof = transcript.challenge_xof(b"c");
+            let c = transcript::sample_challenge(&mut xof);
+
+            if !opening::last(&mut za, &mut ya, a.r, &rhata, &c, &w1a, &t0lowa)
+                && !opening::last(&mut zb, &mut yb, b.r, &rhatb, &c, &w1b, &t0lowb)
+                && (0..HELPERS).all(|k| !opening::last(&mut zh[k], &mut yh[k], &rh[k], &rhath[k], &c, &w1h[k], &t0lowh[k])) {
+                for k in 0..HELPERS {
+                    tch[k].tm.vec[M-2] = tmgarbage[k];
+                }
+                return Some(Self { th: tch, h, chash: opening::challenge_to_bytes(&c).unwrap(), za, zb, zh });
+            }
+        }
+    }
+
+    pub fn verify (
+        &self,
+         cka: &CommKey,
+          ta: &CompressedComm,
+       slota: usize,
+         ckb: &CommKey,
+          tb: &CompressedComm,
+       slotb: usize
+    ) -> bool {
+        if slota >= M || slotb >= M || ta.key_id != cka.id || tb.key_id != ckb.id
+            || self.th.iter().any(|th| th.key_id != cka.id) {
+            return true;
+        }
+        if self.h.iter().any(|h| h.coeffs[..R].iter().any(|&x| x != 0)) {
+            return true;
+        }
+        let (ta, tb) = (ta.expand(), tb.expand());
+        let th: [ExpandedComm; HELPERS] = std::array::from_fn(|k| self.th[k].expand());
+        let mut transcript = Self::statement(cka, &ta, slota, ckb, &tb, slotb);
+        let c = opening::challenge_from_bytes(&self.chash);
+        let x = Self::challenge_x(&transcript);
+        Self::append_helpers(&mut transcript, &th);
+
+        let mut w1a: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+        let mut w1b: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+        let mut w1h: Vec<[PolyVecK; R]> = (0..HELPERS).map(|_| std::array::from_fn(|_| PolyVecK::new())).collect();
+        if opening::verify_first(&mut w1a, &c, &self.za, &ta, cka)
+            || opening::verify_first(&mut w1b, &c, &self.zb, &tb, ckb)
+            || (0..HELPERS).any(|k| opening::verify_first(&mut w1h[k], &c, &self.zh[k], &th[k], cka)) {
+            return true;
+        }
+
+        transcript.append_polyvecs(b"w1-a", &w1a);
+        transcript.append_polyvecs(b"w1-b", &w1b);
+        for w1 in w1h.iter() {
+            transcript.append_polyvecs(b"w1-h", w1);
+        }
+        let mut xof = transcript.challenge_xof(b"product");
+        let alpha: [[Poly; 4]; HELPERS] = std::array::from_fn(|_| std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof)));
+        let beta : [[Poly; R]; HELPERS] = std::array::from_fn(|_| std::array::from_fn(|_| transcript::sample_uniform_poly(&mut xof)));
+
+        let chat: [Poly; R] = std::array::from_fn(|j| {
+            let mut chat = c[j];
+            chat.ntt();
+            chat
+        });
+        let cfull = challenge_full(&chat);
+
+        let mut fpa: [Poly; R] = std::array::from_fn(|j| opening::masked_slot(cka, &ta, slota, &chat[j], &self.za[j]));
+        let mut fpb: [Poly; R] = std::array::from_fn(|j| opening::masked_slot(ckb, &tb, slotb, &chat[j], &self.zb[j]));
+        autobase_verify(&mut fpa);
+        autobase_verify(&mut fpb);
+
+        for k in 0..HELPERS {
+            let (th, zh) = (&th[k], &self.zh[k]);
+            let mut v = Poly::new();
+            for (s, alpha) in alpha[k].iter().enumerate() {
+                let fp = if s % 2 == 0 { &fpa } else { &fpb };
+                let mut fu: [Poly; R] = std::array::from_fn(|j| opening::masked_slot(cka, th, s, &chat[j], &zh[j]));
+                autobase_verify(&mut fu);
+                for j in 0..R {
+                    let mut tmp = Poly::new();
+                    let mut fr = scale(&fu[j], helper_x(&x, k, s));
+                    fr.add(&cfull);
+                    fr.reduce();
+                    Poly::pointwise_montgomery_other(&mut tmp, &fu[j], &fp[j]);
+                    fr.pointwise_montgomery(&cfull);
+                    tmp.add(&fr);
+                    tmp.reduce();
+                    tmp.pointwise_montgomery(alpha);
+                    tmp.pointwise_montgomery(&beta[k][j]);
+                    v.add(&tmp);
+                    v.reduce();
+                }
+            }
+            v.scale_montgomery(MONTSQ as i32);
+            for (j, z) in zh.iter().enumerate() {
+                let mut zshat = z.s;
+                zshat.vec_ntt();
+                let mut g = PolyVecL::pointwise_acc_montgomery(&cka.bm[M-2], &zshat);
+                g.scale_montgomery(MONTSQ as i32);
+                let mut tmp = z.em.vec[M-2];
+                tmp.ntt();
+                g.add(&tmp);
+                poly_shift(&mut tmp, &g, j);
+                v.add(&tmp);
+                v.reduce();
+            }
+            let mut tmp = Poly::new();
+            Poly::pointwise_montgomery_other(&mut tmp, &cfull, &th.tm.vec[M-2]);
+            tmp.scale_montgomery(MONTSQ as i32);
+            v.sub(&tmp);
+            let v = canonical(v);
+
+            let mut hhat = self.h[k];
+            hhat.ntt();
+            let vpr: [Poly; R] = std::array::from_fn(|j| {
+                let mut f = PolyVecM::new();
+                for s in 0..4 {
+                    f.vec[s] = opening::masked_slot(cka, th, s, &chat[j], &zh[j]);
+                }
+                let mut d = trace_sums(&f);
+                let mut mask = Poly::new();
+                Poly::sub_other(&mut mask, &th.tm.vec[M-1], &hhat);
+                mask.reduce();
+                let mut tmp = Poly::new();
+                Poly::pointwise_montgomery_other(&mut tmp, &chat[j], &mask);
+                tmp.scale_montgomery(MONTSQ as i32);
+                let mut g = opening::masked_slot(cka, th, M-1, &Poly::new(), &zh[j]);
+                g.sub(&tmp);
+                d.add(&g);
+                canonical(d)
+            });
+
+            transcript.append_poly(b"tm-garbage", &th.tm.vec[M-2]);
+            transcript.append_poly(b"v", &v);
+            transcript.append_poly(b"h", &self.h[k]);
+            transcript.append_polys(b"vprime", &vpr);
+        }
+        let mut xof = transcript.challenge_xof(b"c");
+        let c2 = transcript::sample_challenge(&mut xof);
+        opening::verify_last(&self.chash, &c2)
+    }
+
+    fn challenge_x(statement: &Transcript) -> [i32; XS] {
+        let mut xof = statement.clone().challenge_xof(b"x");
+        std::array::from_fn(|_| sample_x(&mut xof))
+    }
+
+    /* tm[M-2] only gets the product proof garbage later */
+    fn append_helpers(transcript: &mut Transcript, th: &[ExpandedComm; HELPERS]) {
+        for th in th.iter() {
+            transcript.append_polys(b"t1-h", &th.t1.vec);
+            transcript.append_polys(b"tm-h", &th.tm.vec[..(M-2)]);
+            transcript.append_poly(b"tm-h-last", &th.tm.vec[M-1]);
+        }
+    }
+
+    fn statement(cka: &CommKey, ta: &ExpandedComm, slota: usize, ckb: &CommKey, tb: &ExpandedComm, slotb: usize) -> Transcript {
+        let mut transcript = Transcript::new(DOMAIN);
+        transcript.append(b"key-id-a", &cka.id.0);
+        transcript.append_polys(b"t1-a", &ta.t1.vec);
+        transcript.append_polys(b"tm-a", &ta.tm.vec);
+        transcript.append(b"slot-a", &(slota as u64).to_le_bytes());
+        transcript.append(b"key-id-b", &ckb.id.0);
+        transcript.append_polys(b"t1-b", &tb.t1.vec);
+        transcript.append_polys(b"tm-b", &tb.tm.vec);
+        transcript.append(b"slot-b", &(slotb as u64).to_le_bytes());
+        transcript
+    }
+}
@@ -0,0 +1,35 @@
+use irelzk_rs::{addition::Proof, comm::commitment::CompressedComm, params::SYMBYTES};
+use rand::{rngs::OsRng, RngCore};
+
+#[test]
+fn test_prove_verify() {
+    let mut rho = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut rho);
+    let a = [OsRng.next_u64(), OsRng.next_u64()];
+    let b = [OsRng.next_u64(), OsRng.next_u64()];
+    let (p, t) = Proof::prove(&rho, &a, &b);
+    assert!(!Proof::verify(&p, &t, &rho));
+}
+
+#[test]
+fn test_verify_wrong_rho() {
+    let rho = [1u8; SYMBYTES];
+    let (p, t) = Proof::prove(&rho, &[3, 5], &[7, 11]);
+    assert!(Proof::verify(&p, &t, &[2u8; SYMBYTES]));
+}
+
+#[test]
+fn test_verify_tampered_commitment() {
+    let rho = [5u8; SYMBYTES];
+    let (p, mut t) = Proof::prove(&rho, &[u64::MAX, 1], &[1, 2]);
+    t.tm.vec[0].coeffs[3] ^= 1;
+    assert!(Proof::verify(&p, &t, &rho));
+}
+
+#[test]
+fn test_verify_compressed_bytes() {
+    let rho = [9u8; SYMBYTES];
+    let (p, t) = Proof::prove(&rho, &[12, 34], &[56, 78]);
+    let t = CompressedComm::from_bytes(&t.to_bytes()).unwrap();
+    assert!(!Proof::verify(&p, &t, &rho));
+}
@@ -1,4 +1,5 @@
-use irelzk_rs::crypto::aes256::Aes256Ctx;
+use irelzk_rs::{crypto::aes256::{Aes256Ctx, AES256CTR_BLOCKBYTES}, poly_arith::poly::Poly};
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 
 #[test]
 fn test_squeezeblocks() {
@@ -40,3 +41,96 @@ fn test_prf() {
 
     assert_eq!(out1, out2);
 }
+
+#[test]
+fn test_rng_matches_keystream() {
+    if !is_x86_feature_detected!("aes") {
+        eprintln!("skipping test: aes-ni not available on this cpu");
+        return;
+    }
+
+    let key = [7u8; 32];
+    let nonce = 5;
+    let mut ctx1 = Aes256Ctx::init(&key, nonce);
+    let mut ctx2 = Aes256Ctx::init(&key, nonce);
+
+    let mut out1 = vec![0u8; 256];
+    let mut out2 = vec![0u8; 256];
+    ctx1.squeezeblocks(&mut out1, 256/64);
+    for chunk in out2.chunks_mut(13) {
+        ctx2.fill_bytes(chunk);
+    }
+
+    assert_eq!(out1, out2);
+}
+
+#[test]
+fn test_rng_from_seed() {
+    if !is_x86_feature_detected!("aes") {
+        eprintln!("skipping test: aes-ni not available on this cpu");
+        return;
+    }
+
+    let seed = [3u8; 32];
+    let mut rng1 = Aes256Ctx::from_seed(seed);
+    let mut rng2 = Aes256Ctx::from_seed(seed);
+    assert_eq!(rng1.next_u64(), rng2.next_u64());
+    assert_eq!(rng1.next_u32(), rng2.next_u32());
+
+    let mut a = Poly::new();
+    let mut b = Poly::new();
+    Poly::uniform_preinit(&mut a, &mut rng1);
+    Poly::uniform_preinit(&mut b, &mut rng2);
+    assert_eq!(a.coeffs, b.coeffs);
+}
+
+/* Replays a fixed byte string, so a sampler can be run on a known stream */
+struct Replay<'a>(&'a [u8]);
+
+impl RngCore for Replay<'_> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let (head, tail) = self.0.split_at(dest.len());
+        dest.copy_from_slice(head);
+        self.0 = tail;
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for Replay<'_> {}
+
+#[test]
+fn test_preinit_uses_buffered_keystream() {
+    if !is_x86_feature_detected!("aes") {
+        eprintln!("skipping test: aes-ni not available on this cpu");
+        return;
+    }
+
+    let seed = [7u8; 32];
+    let mut stream = [0u8; 13 + AES256CTR_BLOCKBYTES];
+    Aes256Ctx::from_seed(seed).fill_bytes(&mut stream);
+
+    let mut rng = Aes256Ctx::from_seed(seed);
+    let mut skip = [0u8; 13];
+    rng.fill_bytes(&mut skip);
+    let mut a = Poly::new();
+    let mut b = Poly::new();
+    Poly::trinary_preinit(&mut a, &mut rng);
+    Poly::trinary_preinit(&mut b, &mut Replay(&stream[13..]));
+    assert_eq!(a.coeffs, b.coeffs);
+}
@@ -0,0 +1,97 @@
+use irelzk_rs::{
+    comm::commitment::{Comm, CommKey, CommRnd, CompressedComm, COMPRESSED_COMM_BYTES},
+    params::{K, M, SYMBYTES},
+    poly_arith::{poly::Poly, polyvec::PolyVecM}
+};
+use rand::{rngs::OsRng, RngCore};
+
+#[test]
+fn test_commit_keeps_randomness() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let ck = CommKey::expand(&seed);
+    let mut msg = PolyVecM::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], &seed, i as u16);
+    }
+    let r = CommRnd::generate();
+    let s = r.s;
+    let t = Comm::commit(&ck, &r, &msg);
+    assert_eq!(s.vec[0].coeffs, r.s.vec[0].coeffs);
+    assert!(!t.verify_opening(&ck, &r, &msg, 1));
+}
+
+#[test]
+fn test_verify_opening_rejects() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let ck = CommKey::expand(&seed);
+    let mut msg = PolyVecM::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], &seed, i as u16);
+    }
+    let mut r = CommRnd::generate();
+    let t = Comm::commit(&ck, &r, &msg);
+
+    msg.vec[1].coeffs[5] += 1;
+    assert!(t.verify_opening(&ck, &r, &msg, 1));
+    msg.vec[1].coeffs[5] -= 1;
+
+    assert!(t.verify_opening(&CommKey::expand(&[23u8; SYMBYTES]), &r, &msg, 1));
+
+    r.e.vec[0].coeffs[0] = 2;
+    let t = Comm::commit(&ck, &r, &msg);
+    assert!(t.verify_opening(&ck, &r, &msg, 1));
+    assert!(!t.verify_opening(&ck, &r, &msg, 2));
+}
+
+#[test]
+fn test_compressed_comm_bytes() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let ck = CommKey::expand(&seed);
+    let mut msg = PolyVecM::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], &seed, i as u16);
+    }
+    let r = CommRnd::generate();
+    let tc = Comm::commit(&ck, &r, &msg).compress();
+    let bytes = tc.to_bytes();
+    assert_eq!(bytes.len(), COMPRESSED_COMM_BYTES);
+    let tc2 = CompressedComm::from_bytes(&bytes).unwrap();
+    assert_eq!(tc2.key_id, ck.id);
+    for i in 0..K {
+        assert_eq!(tc2.t1.vec[i].coeffs, tc.t1.vec[i].coeffs);
+    }
+    assert_eq!(tc2.to_bytes(), bytes);
+
+    assert!(CompressedComm::from_bytes(&bytes[1..]).is_none());
+    let mut bad = bytes.clone();
+    let n = bad.len();
+    bad[(n - 4)..].copy_from_slice(&[0xFF; 4]);
+    assert!(CompressedComm::from_bytes(&bad).is_none());
+}
+
+#[test]
+fn test_commit_batch() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let ck = CommKey::expand(&seed);
+    let mut msg = PolyVecM::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], &seed, i as u16);
+    }
+    let r: Vec<CommRnd> = (0..19).map(|_| CommRnd::generate()).collect();
+    let msgs: Vec<PolyVecM> = (0..19).map(|i| {
+        let mut m = msg;
+        m.vec[0].coeffs[0] = i;
+        m
+    }).collect();
+    let t = Comm::commit_batch(&ck, &r, &msgs);
+    assert_eq!(t.len(), 19);
+    for i in 0..19 {
+        let ti = Comm::commit(&ck, &r[i], &msgs[i]);
+        assert_eq!(bytemuck::bytes_of(&t[i].t0), bytemuck::bytes_of(&ti.t0));
+        assert_eq!(bytemuck::bytes_of(&t[i].tm), bytemuck::bytes_of(&ti.tm));
+    }
+}
@@ -0,0 +1,101 @@
+use std::thread;
+
+use irelzk_rs::{
+    addition::Proof,
+    comm::{
+        commitment::{Comm, CommKey, CommKeyId, CommRnd, COMMKEY_BYTES},
+        opening::OpeningProof
+    },
+    params::{ChallengeDist, BETA, CHALLENGE_DIST, D, GAMMA1, GAMMA2, K, L, M, N, PARAMS_ID, Q, R, RND_NORM, SYMBYTES},
+    poly_arith::polyvec::PolyVecM
+};
+
+#[test]
+fn test_commkey_bytes() {
+    let ck = CommKey::expand(&[11u8; SYMBYTES]);
+    let bytes = ck.to_bytes();
+    assert_eq!(bytes.len(), COMMKEY_BYTES);
+    let ck2 = CommKey::from_bytes(&bytes).unwrap();
+    assert_eq!(ck2.rho, ck.rho);
+    assert_eq!(ck2.to_bytes(), bytes);
+
+    let (p, t) = Proof::prove_with_key(&ck2, &[1, 2], &[3, 4]);
+    assert!(!Proof::verify(&p, &t, &[11u8; SYMBYTES]));
+}
+
+#[test]
+fn test_commkey_bytes_invalid() {
+    let mut bytes = CommKey::expand(&[12u8; SYMBYTES]).to_bytes();
+    assert!(CommKey::from_bytes(&bytes[1..]).is_none());
+    bytes[2*SYMBYTES..(2*SYMBYTES + 4)].copy_from_slice(&Q.to_le_bytes());
+    assert!(CommKey::from_bytes(&bytes).is_none());
+}
+
+#[test]
+fn test_commkey_bytes_digest() {
+    let bytes = CommKey::expand(&[18u8; SYMBYTES]).to_bytes();
+    let mut tampered = bytes.clone();
+    tampered[COMMKEY_BYTES - 1] ^= 1;
+    assert!(CommKey::from_bytes(&tampered).is_none());
+
+    /* the same matrices claimed for another rho */
+    let mut tampered = bytes.clone();
+    tampered[0] ^= 1;
+    assert!(CommKey::from_bytes(&tampered).is_none());
+    assert!(CommKey::from_bytes(&bytes).is_some());
+}
+
+#[test]
+fn test_commkey_shared() {
+    let ck = CommKey::shared(&[13u8; SYMBYTES]);
+    let handles: Vec<_> = (0..4u64).map(|i| {
+        let ck = ck.clone();
+        thread::spawn(move || {
+            let (p, t) = Proof::prove_with_key(&ck, &[i, i], &[i + 1, 0]);
+            Proof::verify_with_key(&p, &t, &ck)
+        })
+    }).collect();
+    for h in handles {
+        assert!(!h.join().unwrap());
+    }
+}
+
+#[test]
+fn test_commkey_id() {
+    let ck = CommKey::expand(&[14u8; SYMBYTES]);
+    assert_eq!(ck.id, CommKeyId::derive(&[14u8; SYMBYTES]));
+    assert_ne!(ck.id, CommKeyId::derive(&[15u8; SYMBYTES]));
+    assert_ne!(ck.b0[0].vec[0].coeffs, ck.bt[0].vec[0].coeffs);
+    assert_ne!(ck.b0[0].vec[0].coeffs, ck.bm[0].vec[0].coeffs);
+}
+
+#[test]
+fn test_commkey_id_mismatch() {
+    let ck = CommKey::expand(&[16u8; SYMBYTES]);
+    let msg = PolyVecM::new();
+    let r = CommRnd::generate();
+    let mut t = Comm::commit(&ck, &r, &msg);
+    assert_eq!(t.key_id, ck.id);
+
+    let p = OpeningProof::prove(&ck, &t, &r, &msg).unwrap();
+    assert!(!p.verify(&ck, &t.compress()));
+    t.key_id = CommKeyId::derive(&[17u8; SYMBYTES]);
+    assert!(p.verify(&ck, &t.compress()));
+
+    let (p, mut t) = Proof::prove_with_key(&ck, &[5, 6], &[7, 8]);
+    t.key_id = CommKeyId([0u8; SYMBYTES]);
+    assert!(Proof::verify_with_key(&p, &t, &ck));
+}
+
+#[test]
+fn test_params_id() {
+    let dist = match CHALLENGE_DIST {
+        ChallengeDist::Prehash => "prehash".to_string(),
+        ChallengeDist::FixedWeight(tau) => format!("fixed-weight TAU={}", tau),
+    };
+    let id = format!(
+        "irelzk N={} Q={} K={} L={} M={} R={} D={} GAMMA1={} GAMMA2={} CHALLENGE={} RND_NORM={} BETA={}",
+        N, Q, K, L, M, R, D, GAMMA1, GAMMA2, dist, RND_NORM, BETA
+    );
+    assert_eq!(PARAMS_ID, id.as_bytes());
+}
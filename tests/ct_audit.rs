@@ -0,0 +1,219 @@
+#![cfg(feature = "ct-audit")]
+#![allow(dead_code)]
+
+// dudect-style timing audit: every target is timed on a fixed-input class and a
+// random-input class, and Welch's t-test decides whether the two timing
+// distributions differ. Run with `cargo test --features ct-audit -- --nocapture`.
+
+use std::{arch::x86_64::_rdtsc, hint::black_box};
+
+use irelzk_rs::{
+    addition::Proof,
+    comm::{commitment::{CommKey, CommRnd}, opening},
+    crypto::aes256::Aes256Ctx,
+    params::{N, R, SYMBYTES},
+    poly_arith::{
+        poly::{Poly, REJ_UNIFORM_BUFLEN},
+        polyvec::{PolyVecK, PolyVecM}
+    }
+};
+use rand::{rngs::OsRng, Rng, RngCore};
+
+const T_THRESHOLD: f64 = 10.0;
+const SAMPLES: usize = 20000;
+const CROP_PERCENTILE: f64 = 0.9;
+
+#[derive(Default)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        let den = (var0 / self.n[0] + var1 / self.n[1]).sqrt();
+        if den == 0.0 {
+            return 0.0;
+        }
+        (self.mean[0] - self.mean[1]) / den
+    }
+}
+
+fn classes(n: usize) -> Vec<usize> {
+    (0..n).map(|_| OsRng.gen_range(0..2)).collect()
+}
+
+fn measure<I>(classes: &[usize], inputs: &mut [I], mut f: impl FnMut(&mut I)) -> f64 {
+    let mut times: Vec<u64> = Vec::with_capacity(inputs.len());
+    for input in inputs.iter_mut() {
+        let start = unsafe { _rdtsc() };
+        f(black_box(input));
+        let end = unsafe { _rdtsc() };
+        times.push(end - start);
+    }
+
+    let mut sorted = times.clone();
+    sorted.sort_unstable();
+    let crop = sorted[((sorted.len() as f64) * CROP_PERCENTILE) as usize];
+
+    let mut welch = Welch::default();
+    for (i, &x) in times.iter().enumerate() {
+        if x <= crop {
+            welch.push(classes[i], x as f64);
+        }
+    }
+    welch.t()
+}
+
+fn report(name: &str, t: f64, constant_time: bool) {
+    let verdict = if t.abs() > T_THRESHOLD { "LEAKS" } else { "ok" };
+    eprintln!("{:<24} |t| = {:>8.2}  {} {}", name, t.abs(), verdict,
+              if constant_time { "" } else { "(not claimed constant time)" });
+    if constant_time {
+        assert!(t.abs() <= T_THRESHOLD, "{} leaks timing: |t| = {:.2}", name, t.abs());
+    }
+}
+
+fn random_seed() -> [u8; SYMBYTES] {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    seed
+}
+
+#[test]
+fn audit_trinary_preinit() {
+    let cls = classes(SAMPLES);
+    let fixed = [0u8; SYMBYTES];
+    let mut inputs: Vec<Aes256Ctx> = cls.iter().map(|&c| {
+        if c == 0 { Aes256Ctx::init(&fixed, 0) } else { Aes256Ctx::init(&random_seed(), 0) }
+    }).collect();
+    let mut r = Poly::new();
+    let t = measure(&cls, &mut inputs, |state| {
+        Poly::trinary_preinit(&mut r, state);
+        black_box(&r);
+    });
+    report("Poly::trinary_preinit", t, true);
+}
+
+#[test]
+fn audit_cbd_preinit() {
+    let cls = classes(SAMPLES);
+    let fixed = [0u8; SYMBYTES];
+    let mut inputs: Vec<Aes256Ctx> = cls.iter().map(|&c| {
+        if c == 0 { Aes256Ctx::init(&fixed, 0) } else { Aes256Ctx::init(&random_seed(), 0) }
+    }).collect();
+    let mut r = Poly::new();
+    let t = measure(&cls, &mut inputs, |state| {
+        Poly::cbd_preinit(&mut r, state, 2);
+        black_box(&r);
+    });
+    report("Poly::cbd_preinit", t, true);
+}
+
+#[test]
+fn audit_gaussian_preinit() {
+    let cls = classes(SAMPLES/10);
+    let fixed = [0u8; SYMBYTES];
+    let mut inputs: Vec<Aes256Ctx> = cls.iter().map(|&c| {
+        if c == 0 { Aes256Ctx::init(&fixed, 0) } else { Aes256Ctx::init(&random_seed(), 0) }
+    }).collect();
+    let mut r = Poly::new();
+    let t = measure(&cls, &mut inputs, |state| {
+        Poly::gaussian_preinit(&mut r, state, 3.2);
+        black_box(&r);
+    });
+    report("Poly::gaussian_preinit", t, true);
+}
+
+#[test]
+fn audit_check_norm() {
+    let cls = classes(SAMPLES);
+    let mut inputs: Vec<Poly> = cls.iter().map(|&c| {
+        let mut a = Poly::new();
+        if c == 1 {
+            Poly::uniform_gamma(&mut a, &random_seed(), 0);
+        }
+        a
+    }).collect();
+    let t = measure(&cls, &mut inputs, |a| {
+        black_box(Poly::check_norm(a, 1 << 17));
+    });
+    report("Poly::check_norm", t, true);
+}
+
+#[test]
+fn audit_encode() {
+    let cls = classes(SAMPLES);
+    let mut inputs: Vec<([u64; 2], [u64; 2])> = cls.iter().map(|&c| {
+        if c == 0 {
+            ([0u64; 2], [0u64; 2])
+        } else {
+            ([OsRng.next_u64(), OsRng.next_u64()], [OsRng.next_u64(), OsRng.next_u64()])
+        }
+    }).collect();
+    let mut msg = PolyVecM::new();
+    let t = measure(&cls, &mut inputs, |(a, b)| {
+        Proof::encode(&mut msg, a, b);
+        black_box(&msg);
+    });
+    report("Proof::encode", t, true);
+}
+
+#[test]
+fn audit_rej_uniform_avx() {
+    let cls = classes(SAMPLES);
+    let mut inputs: Vec<Box<[u8; REJ_UNIFORM_BUFLEN]>> = cls.iter().map(|&c| {
+        let mut buf = Box::new([0u8; REJ_UNIFORM_BUFLEN]);
+        if c == 1 {
+            OsRng.fill_bytes(buf.as_mut());
+        }
+        buf
+    }).collect();
+    let mut r = Poly::new();
+    let t = measure(&cls, &mut inputs, |buf| {
+        black_box(Poly::rej_uniform_avx(&mut r.coeffs, buf));
+    });
+    report("Poly::rej_uniform_avx", t, false);
+}
+
+#[test]
+fn audit_opening_last() {
+    let samples = SAMPLES / 20;
+    let cls = classes(samples);
+    let ck = CommKey::expand(&[0u8; SYMBYTES]);
+    let fixed = (CommRnd::new(), CommRnd::new().to_ntt());
+    let random: Vec<(CommRnd, CommRnd)> = (0..samples).map(|_| {
+        let r = CommRnd::generate();
+        let rhat = r.to_ntt();
+        (r, rhat)
+    }).collect();
+
+    let mut c = [Poly::new(); R];
+    opening::challenge_prehash(&mut c, &[0x5Au8; N/4]);
+    let t0low = PolyVecK::new();
+    let seed = random_seed();
+
+    let mut inputs: Vec<(usize, [CommRnd; R], [PolyVecK; R])> = (0..samples).map(|i| {
+        let mut y = opening::generate_y(&seed, 0);
+        let mut w1: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+        let mut g: [PolyVecM; R] = std::array::from_fn(|_| PolyVecM::new());
+        opening::first(&mut w1, &mut g, &mut y, &ck);
+        (i, y, w1)
+    }).collect();
+    let mut z: [CommRnd; R] = std::array::from_fn(|_| CommRnd::new());
+    let t = measure(&cls, &mut inputs, |(i, y, w1)| {
+        let (r, rhat) = if cls[*i] == 0 { &fixed } else { &random[*i] };
+        black_box(opening::last(&mut z, y, r, rhat, &c, w1, &t0low));
+    });
+    report("opening::last", t, false);
+}
@@ -0,0 +1,63 @@
+use irelzk_rs::{
+    comm::{
+        commitment::{Comm, CommKey, CommRnd},
+        message::{Domain, Message}
+    },
+    equality::{EqualityProof, Witness},
+    params::SYMBYTES
+};
+
+#[test]
+fn test_equality_proof() {
+    let ck1 = CommKey::expand(&[31u8; SYMBYTES]);
+    let ck2 = CommKey::expand(&[32u8; SYMBYTES]);
+    let m1 = Message::from_u64s(&[42, 7, 9], Domain::Coeff).unwrap();
+    let mut m2 = Message::from_u64s(&[0; 33], Domain::Coeff).unwrap();
+    m2.msg.vec[1] = m1.msg.vec[0];
+    let (r1, r2) = (CommRnd::generate(), CommRnd::generate());
+    let t1 = Comm::commit(&ck1, &r1, &m1.msg);
+    let t2 = Comm::commit(&ck2, &r2, &m2.msg);
+
+    let p = EqualityProof::prove(
+        &Witness { ck: &ck1, t: &t1, r: &r1, msg: &m1.msg, slot: 0 },
+        &Witness { ck: &ck2, t: &t2, r: &r2, msg: &m2.msg, slot: 1 },
+    ).unwrap();
+    let (tc1, tc2) = (t1.compress(), t2.compress());
+    assert!(!p.verify(&ck1, &tc1, 0, &ck2, &tc2, 1));
+    assert!(p.verify(&ck1, &tc1, 0, &ck2, &tc2, 0));
+    assert!(p.verify(&ck2, &tc1, 0, &ck2, &tc2, 1));
+
+    let mut tc2 = tc2;
+    tc2.tm.vec[1].coeffs[0] = (tc2.tm.vec[1].coeffs[0] + 1) % irelzk_rs::params::Q;
+    assert!(p.verify(&ck1, &tc1, 0, &ck2, &tc2, 1));
+}
+
+#[test]
+#[should_panic]
+fn test_equality_proof_unequal() {
+    let ck = CommKey::expand(&[33u8; SYMBYTES]);
+    let m1 = Message::from_u64s(&[1], Domain::Coeff).unwrap();
+    let m2 = Message::from_u64s(&[2], Domain::Coeff).unwrap();
+    let (r1, r2) = (CommRnd::generate(), CommRnd::generate());
+    let t1 = Comm::commit(&ck, &r1, &m1.msg);
+    let t2 = Comm::commit(&ck, &r2, &m2.msg);
+    EqualityProof::prove(
+        &Witness { ck: &ck, t: &t1, r: &r1, msg: &m1.msg, slot: 0 },
+        &Witness { ck: &ck, t: &t2, r: &r2, msg: &m2.msg, slot: 0 },
+    );
+}
+
+#[test]
+fn test_equality_verify_rejects_unequal() {
+    let ck = CommKey::expand(&[34u8; SYMBYTES]);
+    let m1 = Message::from_u64s(&[1], Domain::Coeff).unwrap();
+    let m2 = Message::from_u64s(&[2], Domain::Coeff).unwrap();
+    let (r1, r2) = (CommRnd::generate(), CommRnd::generate());
+    let t1 = Comm::commit(&ck, &r1, &m1.msg);
+    let t2 = Comm::commit(&ck, &r2, &m2.msg);
+    let p = EqualityProof::prove_unchecked(
+        &Witness { ck: &ck, t: &t1, r: &r1, msg: &m1.msg, slot: 0 },
+        &Witness { ck: &ck, t: &t2, r: &r2, msg: &m2.msg, slot: 0 },
+    ).unwrap();
+    assert!(p.verify(&ck, &t1.compress(), 0, &ck, &t2.compress(), 0));
+}
@@ -0,0 +1,115 @@
+use irelzk_rs::{
+    comm::{
+        commitment::{Comm, CommKey, CommRnd},
+        opening::{OpeningProof, MAX_RND_NORM}
+    },
+    params::{M, N, Q, SYMBYTES},
+    poly_arith::{poly::Poly, polyvec::PolyVec}
+};
+
+fn message(seed: &[u8; SYMBYTES]) -> PolyVec<M> {
+    let mut msg = PolyVec::<M>::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], seed, i as u16);
+    }
+    msg
+}
+
+fn copy(r: &CommRnd) -> CommRnd {
+    CommRnd { s: r.s, e: r.e, em: r.em, norm: r.norm }
+}
+
+fn assert_eq_mod_q<const S: usize>(a: &PolyVec<S>, b: &PolyVec<S>) {
+    for i in 0..S {
+        for j in 0..N {
+            assert_eq!((a.vec[i].coeffs[j] as i64 - b.vec[i].coeffs[j] as i64) % Q as i64, 0);
+        }
+    }
+}
+
+#[test]
+fn test_comm_add_sub() {
+    let ck = CommKey::expand(&[1u8; SYMBYTES]);
+    let (m1, m2) = (message(&[2u8; SYMBYTES]), message(&[3u8; SYMBYTES]));
+    let (r1, r2) = (CommRnd::generate(), CommRnd::generate());
+
+    let mut r12 = copy(&r1);
+    r12.add(&r2);
+    let mut m12 = m1;
+    m12.add(&m2);
+    let t12 = Comm::commit(&ck, &r12, &m12);
+
+    let mut t1 = Comm::commit(&ck, &r1, &m1);
+    let t2 = Comm::commit(&ck, &r2, &m2);
+    t1.add(&t2);
+    assert_eq_mod_q(&t1.t0, &t12.t0);
+    assert_eq_mod_q(&t1.tm, &t12.tm);
+    assert_eq!(r12.norm, 2);
+    assert!(!t1.verify_opening(&ck, &r12, &m12, 2));
+
+    let p = OpeningProof::prove(&ck, &t1, &r12, &m12).unwrap();
+    assert!(!p.verify(&ck, &t1.compress()));
+
+    t1.sub(&t2);
+    r12.sub(&r2);
+    m12.sub(&m2);
+    assert_eq!(r12.norm, 1);
+    let p = OpeningProof::prove(&ck, &t1, &r12, &m12).unwrap();
+    assert!(!p.verify(&ck, &t1.compress()));
+}
+
+#[test]
+fn test_comm_mul() {
+    let ck = CommKey::expand(&[4u8; SYMBYTES]);
+    let mut msg = message(&[5u8; SYMBYTES]);
+    let mut r = CommRnd::generate();
+    let mut t = Comm::commit(&ck, &r, &msg);
+
+    let mut c = Poly::new();
+    c.coeffs[0] = 1;
+    c.coeffs[3] = -1;
+    t.mul_poly(&c);
+    r.mul_poly(&c);
+    msg.mul_poly(&c);
+    assert_eq!(r.norm, 2);
+    assert!(!t.verify_opening(&ck, &r, &msg, 2));
+
+    t.mul_scalar(-1);
+    r.mul_scalar(-1);
+    msg.mul_scalar(-1);
+
+    let p = OpeningProof::prove(&ck, &t, &r, &msg).unwrap();
+    assert!(!p.verify(&ck, &t.compress()));
+}
+
+#[test]
+fn test_prove_sum_of_five() {
+    let ck = CommKey::expand(&[8u8; SYMBYTES]);
+    let m = message(&[9u8; SYMBYTES]);
+    let mut r = CommRnd::generate();
+    let mut t = Comm::commit(&ck, &r, &m);
+    let mut msg = m;
+    for _ in 1..5 {
+        let r2 = CommRnd::generate();
+        t.add(&Comm::commit(&ck, &r2, &m));
+        r.add(&r2);
+        msg.add(&m);
+    }
+    assert!(r.norm <= MAX_RND_NORM);
+    let p = OpeningProof::prove(&ck, &t, &r, &msg).unwrap();
+    assert!(!p.verify(&ck, &t.compress()));
+}
+
+#[test]
+fn test_prove_norm_too_large() {
+    let ck = CommKey::expand(&[6u8; SYMBYTES]);
+    let mut msg = message(&[7u8; SYMBYTES]);
+    let mut r = CommRnd::generate();
+    let mut t = Comm::commit(&ck, &r, &msg);
+    let c = MAX_RND_NORM as i32 + 1;
+    t.mul_scalar(c);
+    r.mul_scalar(c);
+    msg.mul_scalar(c);
+    assert_eq!(r.norm, MAX_RND_NORM + 1);
+    assert!(OpeningProof::prove(&ck, &t, &r, &msg).is_none());
+}
@@ -0,0 +1,64 @@
+use irelzk_rs::{
+    comm::{
+        commitment::{Comm, CommKey, CommRnd},
+        message::{Domain, Message, CAPACITY},
+        opening::OpeningProof
+    },
+    params::{Q, SYMBYTES}
+};
+use rand::{rngs::OsRng, Rng, RngCore};
+
+const DOMAINS: [Domain; 2] = [Domain::Coeff, Domain::Ntt];
+
+#[test]
+fn test_message_roundtrip() {
+    for domain in DOMAINS {
+        let bits: Vec<bool> = (0..300).map(|_| OsRng.gen()).collect();
+        assert_eq!(Message::from_bits(&bits, domain).unwrap().to_bits().unwrap(), bits);
+
+        let v: Vec<u64> = (0..17).map(|_| OsRng.next_u64()).collect();
+        assert_eq!(Message::from_u64s(&v, domain).unwrap().to_u64s(), v);
+
+        let mut bytes = vec![0u8; 101];
+        OsRng.fill_bytes(&mut bytes);
+        assert_eq!(Message::from_bytes(&bytes, domain).unwrap().to_bytes().unwrap(), bytes);
+
+        let coeffs: Vec<i32> = (0..CAPACITY).map(|_| OsRng.gen_range(0..Q)).collect();
+        assert_eq!(Message::from_zq_coeffs(&coeffs, domain).unwrap().to_zq_coeffs(), coeffs);
+    }
+}
+
+#[test]
+fn test_message_overflow() {
+    assert!(Message::from_bits(&vec![true; CAPACITY + 1], Domain::Coeff).is_none());
+    assert!(Message::from_u64s(&vec![0; CAPACITY / 4 + 1], Domain::Ntt).is_none());
+    assert!(Message::from_zq_coeffs(&[Q], Domain::Coeff).is_none());
+    assert!(Message::from_zq_coeffs(&[-1], Domain::Ntt).is_none());
+
+    let mut m = Message::from_bits(&[true, false], Domain::Ntt).unwrap();
+    let other = Message::from_bits(&[true, true], Domain::Ntt).unwrap();
+    m.msg.add(&other.msg);
+    assert!(m.to_bits().is_none());
+}
+
+#[test]
+fn test_message_homomorphic_u64s() {
+    let ck = CommKey::expand(&[6u8; SYMBYTES]);
+    let a = [u64::MAX, 1 << 40, 12345];
+    let b = [1, 1 << 40, u64::MAX - 12344];
+    let mut ma = Message::from_u64s(&a, Domain::Coeff).unwrap();
+    let mb = Message::from_u64s(&b, Domain::Coeff).unwrap();
+    let (mut ra, rb) = (CommRnd::generate(), CommRnd::generate());
+    let mut ta = Comm::commit(&ck, &ra, &ma.msg);
+    let tb = Comm::commit(&ck, &rb, &mb.msg);
+
+    ta.add(&tb);
+    ra.add(&rb);
+    ma.msg.add(&mb.msg);
+    ma.msg.reduce();
+    let p = OpeningProof::prove(&ck, &ta, &ra, &ma.msg).unwrap();
+    assert!(!p.verify(&ck, &ta.compress()));
+
+    let sum: Vec<u64> = a.iter().zip(b.iter()).map(|(x, y)| x.wrapping_add(*y)).collect();
+    assert_eq!(ma.to_u64s(), sum);
+}
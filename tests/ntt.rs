@@ -90,3 +90,57 @@ fn test_invntt() {
         assert_eq!(diff, 0, "Failed at {}", i);
     }
 }
+
+fn random_poly(bound: u32) -> Poly {
+    let mut a = Poly::new();
+    for x in a.coeffs.iter_mut() {
+        *x = (OsRng.next_u32() % (2*bound + 1)) as i32 - bound as i32;
+    }
+    a
+}
+
+#[test]
+fn test_mul_schoolbook() {
+    let mut a = Poly::new();
+    let mut b = Poly::new();
+    a.coeffs[N-1] = 1;
+    b.coeffs[1] = 2;
+    let c = Poly::mul_schoolbook(&a, &b);
+    assert_eq!(c.coeffs[0], Q - 2);
+    assert!(c.coeffs[1..].iter().all(|&x| x == 0));
+}
+
+#[test]
+fn test_mul() {
+    for bound in [1, 1 << 18, (Q as u32 - 1)/2] {
+        for _ in 0..10 {
+            let a = random_poly(bound);
+            let b = random_poly(bound);
+            let c = Poly::mul(&a, &b);
+            let d = Poly::mul_schoolbook(&a, &b);
+            for i in 0..N {
+                assert_eq!(c.coeffs[i].rem_euclid(Q), d.coeffs[i], "Failing at index {}", i);
+                assert!(c.coeffs[i].abs() <= Q, "Failing at index {}", i);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_mul_sparse_ternary() {
+    for (weight, bound) in [(1, 1), (22, 1), (32, 1 << 13), (N, 1 << 18)] {
+        let mut c = Poly::new();
+        for _ in 0..weight {
+            let j = (OsRng.next_u32() as usize) % N;
+            c.coeffs[j] = if OsRng.next_u32() & 1 == 0 { 1 } else { -1 };
+        }
+        let a = random_poly(bound);
+        let mut r = Poly::new();
+        Poly::mul_sparse_ternary(&mut r, &c, &a);
+        let d = Poly::mul_schoolbook(&c, &a);
+        for i in 0..N {
+            assert_eq!(r.coeffs[i].rem_euclid(Q), d.coeffs[i], "Failing at index {}", i);
+            assert!(r.coeffs[i].unsigned_abs() <= weight as u32 * bound, "Failing at index {}", i);
+        }
+    }
+}
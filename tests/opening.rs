@@ -0,0 +1,97 @@
+use irelzk_rs::{
+    comm::{
+        commitment::{Comm, CommKey, CommRnd, CompressedComm},
+        opening::{self, OpeningProof}
+    },
+    params::{M, N, R, SYMBYTES},
+    poly_arith::{poly::Poly, polyvec::{PolyVecK, PolyVecM}}
+};
+use rand::{rngs::OsRng, RngCore};
+
+#[test]
+fn test_opening_proof() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let ck = CommKey::expand(&seed);
+    let mut msg = PolyVecM::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], &seed, i as u16);
+    }
+    let r = CommRnd::generate();
+    let t = Comm::commit(&ck, &r, &msg);
+    let p = OpeningProof::prove(&ck, &t, &r, &msg).unwrap();
+    let tc = CompressedComm::from_bytes(&t.compress().to_bytes()).unwrap();
+    assert!(!p.verify(&ck, &tc));
+}
+
+#[test]
+fn test_opening_proof_tampered() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let ck = CommKey::expand(&seed);
+    let mut msg = PolyVecM::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], &seed, i as u16);
+    }
+    let r = CommRnd::generate();
+    let t = Comm::commit(&ck, &r, &msg);
+    let p = OpeningProof::prove(&ck, &t, &r, &msg).unwrap();
+    let mut tc = t.compress();
+    assert!(p.verify(&CommKey::expand(&[8u8; SYMBYTES]), &tc));
+    tc.t1.vec[0].coeffs[0] += 1;
+    assert!(p.verify(&ck, &tc));
+}
+
+#[test]
+#[should_panic]
+fn test_opening_proof_wrong_message() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let ck = CommKey::expand(&seed);
+    let mut msg = PolyVecM::new();
+    for i in 0..M {
+        Poly::uniform_random(&mut msg.vec[i], &seed, i as u16);
+    }
+    let r = CommRnd::generate();
+    let t = Comm::commit(&ck, &r, &msg);
+    msg.vec[0].coeffs[0] += 1;
+    OpeningProof::prove(&ck, &t, &r, &msg);
+}
+
+#[test]
+fn test_challenge_bytes() {
+    let mut w: [PolyVecK; R] = std::array::from_fn(|_| PolyVecK::new());
+    w[1].vec[2].coeffs[3] = 5;
+    let c = opening::challenge(&w);
+    assert_eq!(c.map(|p| p.coeffs), opening::challenge(&w).map(|p| p.coeffs));
+    w[1].vec[2].coeffs[3] = 6;
+    assert_ne!(c.map(|p| p.coeffs), opening::challenge(&w).map(|p| p.coeffs));
+
+    let bytes = opening::challenge_to_bytes(&c).unwrap();
+    let mut c2: [Poly; R] = std::array::from_fn(|_| Poly::new());
+    opening::challenge_prehash(&mut c2, &bytes);
+    assert_eq!(c.map(|p| p.coeffs), c2.map(|p| p.coeffs));
+
+    let chash: [u8; N/4] = std::array::from_fn(|i| (i * 37 + 11) as u8);
+    opening::challenge_prehash(&mut c2, &chash);
+    let bytes = opening::challenge_to_bytes(&c2).unwrap();
+    let mut c3: [Poly; R] = std::array::from_fn(|_| Poly::new());
+    opening::challenge_prehash(&mut c3, &bytes);
+    assert_eq!(c2.map(|p| p.coeffs), c3.map(|p| p.coeffs));
+
+    let mut bytes = opening::challenge_to_bytes(&c).unwrap();
+    assert!(!opening::verify_last(&bytes, &c));
+    assert_eq!(opening::challenge_from_bytes(&bytes).map(|p| p.coeffs), c.map(|p| p.coeffs));
+    let i = bytes.iter().position(|&b| b & 3 == 0).unwrap();
+    bytes[i] |= 1;
+    assert_eq!(opening::challenge_from_bytes(&bytes).map(|p| p.coeffs), c.map(|p| p.coeffs));
+    assert!(opening::verify_last(&bytes, &c));
+
+    let mut c4 = c;
+    c4[0].coeffs[1] = 1;
+    assert!(opening::challenge_to_bytes(&c4).is_none());
+    c4 = c;
+    c4[2].coeffs[0] = 2;
+    assert!(opening::challenge_to_bytes(&c4).is_none());
+    assert!(opening::verify_last(&opening::challenge_to_bytes(&c).unwrap(), &c4));
+}
@@ -0,0 +1,121 @@
+use irelzk_rs::{
+    params::{GAMMA1, N, Q, SYMBYTES, T1_BITS, T1_MAX},
+    poly_arith::poly::{
+        Poly, POLYC_PACKEDBYTES, POLYHINT_PACKEDBYTES, POLYT1_PACKEDBYTES,
+        POLYUNIFORM_PACKEDBYTES, POLYZ_PACKEDBYTES
+    }
+};
+use rand::{rngs::OsRng, Rng};
+
+fn random(lo: i32, hi: i32) -> Poly {
+    let mut a = Poly::new();
+    for x in a.coeffs.iter_mut() {
+        *x = OsRng.gen_range(lo..=hi);
+    }
+    a
+}
+
+#[test]
+fn test_pack_uniform() {
+    let mut a = Poly::new();
+    Poly::uniform_random(&mut a, &[1u8; SYMBYTES], 0);
+    a.coeffs[0] = Q - 1;
+    let mut bytes = [0u8; POLYUNIFORM_PACKEDBYTES];
+    Poly::pack_uniform(&mut bytes, &a);
+    let mut b = Poly::new();
+    assert!(!Poly::unpack_uniform(&mut b, &bytes));
+    assert_eq!(a.coeffs, b.coeffs);
+
+    bytes[..4].copy_from_slice(&Q.to_le_bytes());
+    assert!(Poly::unpack_uniform(&mut b, &bytes));
+}
+
+#[test]
+fn test_pack_z() {
+    let mut a = random(-GAMMA1, GAMMA1 - 1);
+    a.coeffs[0] = -GAMMA1;
+    a.coeffs[N-1] = GAMMA1 - 1;
+    let mut bytes = [0u8; POLYZ_PACKEDBYTES];
+    Poly::pack_z(&mut bytes, &a);
+    let mut b = Poly::new();
+    Poly::unpack_z(&mut b, &bytes);
+    assert_eq!(a.coeffs, b.coeffs);
+}
+
+#[test]
+fn test_pack_t1() {
+    let mut a = random(-T1_MAX, T1_MAX);
+    a.coeffs[0] = -T1_MAX;
+    a.coeffs[1] = T1_MAX;
+    let mut bytes = [0u8; POLYT1_PACKEDBYTES];
+    Poly::pack_t1(&mut bytes, &a);
+    let mut b = Poly::new();
+    assert!(!Poly::unpack_t1(&mut b, &bytes));
+    assert_eq!(a.coeffs, b.coeffs);
+
+    bytes[0] = 0xff;
+    bytes[1] = 0x7f;
+    assert!(Poly::unpack_t1(&mut b, &bytes));
+}
+
+#[test]
+fn test_pack_hint() {
+    let a = random(0, 1);
+    let mut bytes = [0u8; POLYHINT_PACKEDBYTES];
+    Poly::pack_hint(&mut bytes, &a);
+    let mut b = Poly::new();
+    Poly::unpack_hint(&mut b, &bytes);
+    assert_eq!(a.coeffs, b.coeffs);
+}
+
+#[test]
+fn test_pack_challenge() {
+    let a = random(-1, 1);
+    let mut bytes = [0u8; POLYC_PACKEDBYTES];
+    Poly::pack_challenge(&mut bytes, &a);
+    let mut b = Poly::new();
+    assert!(!Poly::unpack_challenge(&mut b, &bytes));
+    assert_eq!(a.coeffs, b.coeffs);
+
+    bytes[0] = (bytes[0] & !3) | 2;
+    assert!(Poly::unpack_challenge(&mut b, &bytes));
+}
+
+#[test]
+#[should_panic]
+fn test_pack_z_out_of_range() {
+    let mut a = Poly::new();
+    a.coeffs[0] = GAMMA1;
+    Poly::pack_z(&mut [0u8; POLYZ_PACKEDBYTES], &a);
+}
+
+#[test]
+fn test_pack_matches_pack_bits() {
+    let a = random(0, Q - 1);
+    let mut bytes = [0u8; POLYUNIFORM_PACKEDBYTES];
+    let mut reference = [0u8; POLYUNIFORM_PACKEDBYTES];
+    Poly::pack_uniform(&mut bytes, &a);
+    Poly::pack_bits(&mut reference, &a, 30);
+    assert_eq!(bytes, reference);
+
+    let mut b = Poly::new();
+    let mut c = Poly::new();
+    OsRng.fill(&mut reference[..]);
+    Poly::unpack_uniform(&mut b, &reference);
+    Poly::unpack_bits(&mut c, &reference, 30);
+    assert_eq!(b.coeffs, c.coeffs);
+
+    let a = random(-T1_MAX, T1_MAX);
+    let mut bytes = [0u8; POLYT1_PACKEDBYTES];
+    let mut reference = [0u8; POLYT1_PACKEDBYTES];
+    Poly::pack_t1(&mut bytes, &a);
+    Poly::pack_bits(&mut reference, &a, T1_BITS);
+    assert_eq!(bytes, reference);
+
+    OsRng.fill(&mut reference[..]);
+    assert!(!Poly::unpack_t1(&mut b, &reference) || b.coeffs.iter().any(|x| x.abs() > T1_MAX));
+    Poly::unpack_bits(&mut c, &reference, T1_BITS);
+    for (x, y) in b.coeffs.iter().zip(c.coeffs.iter()) {
+        assert_eq!(*x as i16, *y as u16 as i16);
+    }
+}
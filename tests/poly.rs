@@ -1,5 +1,5 @@
 #![allow(dead_code)]
-use irelzk_rs::{params::{N, SYMBYTES}, poly_arith::poly::Poly};
+use irelzk_rs::{params::{N, Q, SYMBYTES}, poly_arith::poly::{Poly, Subgroup}};
 use rand::{rngs::OsRng, RngCore};
 
 fn bitrev7(a: u8) -> u8 {
@@ -118,3 +118,135 @@ fn test_trace65() {
         assert_eq!(f.coeffs[index], 0, "Failing at index {}", i);
     }
 }
+
+#[test]
+fn test_sigma_ntt() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let mut f = Poly::new();
+    Poly::uniform_random(&mut f, &seed, 0);
+    let mut fhat = f;
+    fhat.ntt();
+    for k in (1..256).step_by(2) {
+        let mut g = Poly::sigma(&f, k as isize);
+        g.ntt();
+        let mut h = Poly::new();
+        Poly::sigma_ntt_other(&mut h, &fhat, k);
+        g.sub(&h);
+        g.freeze();
+        for i in 0..N {
+            assert_eq!(g.coeffs[i] % irelzk_rs::params::Q, 0, "Failing at index {} for k = {}", i, k);
+        }
+    }
+
+    for (k, sigma) in [(65, Poly::sigma65_ntt as fn(&mut Poly)), (129, Poly::sigma129_ntt), (193, Poly::sigma193_ntt)] {
+        let mut g = fhat;
+        let mut h = fhat;
+        g.sigma_ntt(k);
+        sigma(&mut h);
+        assert_eq!(g.coeffs, h.coeffs, "Failing for k = {}", k);
+    }
+}
+
+#[test]
+fn test_trace_ntt() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    let mut f = Poly::new();
+    Poly::uniform_random(&mut f, &seed, 0);
+    let mut fhat = f;
+    fhat.ntt();
+
+    assert_eq!(Subgroup::generated_by(&[65]).elements(), &[1, 65, 129, 193]);
+    assert_eq!(Subgroup::generated_by(&[5, 255]).order(), N);
+    for gens in [&[1][..], &[65], &[33], &[5], &[255], &[5, 255], &[129, 255]] {
+        let h = Subgroup::generated_by(gens);
+        let t = Poly::trace(&f, &h);
+        let mut g = t;
+        g.ntt();
+        let mut that = Poly::new();
+        Poly::trace_ntt_other(&mut that, &fhat, &h);
+        g.sub(&that);
+        g.freeze();
+        assert!(g.coeffs.iter().all(|&x| x % Q == 0), "Failing for {:?}", gens);
+
+        for &k in h.elements() {
+            let mut d = Poly::sigma(&t, k as isize);
+            d.sub(&t);
+            d.freeze();
+            assert!(d.coeffs.iter().all(|&x| x % Q == 0), "Failing for {:?} at k = {}", gens, k);
+        }
+    }
+
+    let mut t = Poly::trace(&f, &Subgroup::generated_by(&[5, 255]));
+    t.freeze();
+    assert!(t.coeffs[1..].iter().all(|&x| x % Q == 0));
+
+    let mut g = fhat;
+    let mut h = fhat;
+    g.trace_ntt(&Subgroup::generated_by(&[65]));
+    h.trace65_ntt();
+    g.sub(&h);
+    g.freeze();
+    assert!(g.coeffs.iter().all(|&x| x % Q == 0));
+}
+
+fn moments(samples: &[i32]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().map(|&x| x as f64).sum::<f64>()/n;
+    let var = samples.iter().map(|&x| (x as f64 - mean).powi(2)).sum::<f64>()/n;
+    (mean, var)
+}
+
+#[test]
+fn test_cbd_output() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    for eta in [1, 2, 4, 16] {
+        let mut samples = Vec::new();
+        for nonce in 0..64 {
+            let mut a = Poly::new();
+            Poly::cbd(&mut a, &seed, nonce, eta);
+            assert!(a.coeffs.iter().all(|&x| x.abs() <= eta as i32));
+            samples.extend_from_slice(&a.coeffs);
+        }
+        let (mean, var) = moments(&samples);
+        assert!(mean.abs() < 0.1*(eta as f64).sqrt(), "eta = {}, mean = {}", eta, mean);
+        assert!((var/(eta as f64/2.0) - 1.0).abs() < 0.1, "eta = {}, var = {}", eta, var);
+    }
+
+    let (mut a, mut b) = (Poly::new(), Poly::new());
+    Poly::cbd(&mut a, &seed, 1, 2);
+    Poly::cbd(&mut b, &seed, 1, 2);
+    assert_eq!(a.coeffs, b.coeffs);
+}
+
+#[test]
+fn test_gaussian_output() {
+    let mut seed = [0u8; SYMBYTES];
+    OsRng.fill_bytes(&mut seed);
+    for sigma in [1.0, 3.2, 20.0] {
+        let mut samples = Vec::new();
+        for nonce in 0..64 {
+            let mut a = Poly::new();
+            Poly::gaussian(&mut a, &seed, nonce, sigma);
+            assert!(a.coeffs.iter().all(|&x| (x.abs() as f64) <= 13.0*sigma));
+            samples.extend_from_slice(&a.coeffs);
+        }
+        let (mean, var) = moments(&samples);
+        assert!(mean.abs() < 0.1*sigma, "sigma = {}, mean = {}", sigma, mean);
+        assert!((var/(sigma*sigma) - 1.0).abs() < 0.1, "sigma = {}, var = {}", sigma, var);
+    }
+}
+
+#[test]
+fn test_inf_norm() {
+    let mut a = Poly::new();
+    assert_eq!(Poly::inf_norm(&a), 0);
+    a.coeffs[5] = 3;
+    a.coeffs[77] = -7;
+    assert_eq!(Poly::inf_norm(&a), 7);
+    a.coeffs[N-1] = irelzk_rs::params::Q - 9;
+    assert_eq!(Poly::inf_norm(&a), 9);
+    assert!(!Poly::check_norm(&a, 10) && Poly::check_norm(&a, 9));
+}
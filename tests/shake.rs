@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
-use irelzk_rs::crypto::shake::Shake128;
+use irelzk_rs::{
+    crypto::{shake::Shake128, transcript::{self, Transcript}},
+    params::{ChallengeDist, CHALLENGE_DIST, GAMMA1, N, Q, R},
+    poly_arith::poly::Poly
+};
+use rand_core::RngCore;
 
 #[test]
 fn test_shake() {
@@ -42,3 +47,158 @@ fn test_shake_againt_known_vectors() {
         assert_eq!(out, expected_output, "Failed for ({}, {}) -- Output: {}", k, v, hex::encode(out));
     }
 }
+
+#[test]
+fn test_shake_rng() {
+    let input = [9u8; 20];
+    let mut out1 = [0u8; 400];
+    let mut out2 = [0u8; 400];
+
+    let mut shake_handle = Shake128::init();
+    shake_handle.absorb(&input);
+    shake_handle.finalize();
+    shake_handle.squeeze(&mut out1);
+
+    shake_handle = Shake128::init();
+    shake_handle.absorb(&input);
+    let mut xof = shake_handle.finalize_xof();
+    for chunk in out2.chunks_mut(7) {
+        xof.fill_bytes(chunk);
+    }
+    assert_eq!(out1, out2);
+}
+
+#[test]
+fn test_xof_reader() {
+    let input = [4u8; 33];
+    let mut out1 = [0u8; 300];
+    let mut out2 = [0u8; 300];
+
+    let mut shake_handle = Shake128::init();
+    shake_handle.absorb(&input);
+    shake_handle.finalize();
+    shake_handle.squeeze(&mut out1);
+
+    shake_handle = Shake128::init();
+    shake_handle.absorb(&input);
+    let mut xof = shake_handle.finalize_xof();
+    xof.read(&mut out2[..5]);
+    let word = xof.read_u32_le();
+    out2[5..9].copy_from_slice(&word.to_le_bytes());
+    xof.read(&mut out2[9..]);
+    assert_eq!(out1, out2);
+}
+
+#[test]
+fn test_xof_poly_uniform() {
+    let mut shake_handle = Shake128::init();
+    shake_handle.absorb(b"poly");
+    let mut xof = shake_handle.finalize_xof();
+    let mut a = Poly::new();
+    xof.read_poly_uniform(&mut a);
+    for i in 0..N {
+        assert!(a.coeffs[i] >= 0 && a.coeffs[i] < Q, "Failing at index {}", i);
+    }
+}
+
+#[test]
+fn test_xof_drives_samplers() {
+    let xof = || {
+        let mut shake_handle = Shake128::init();
+        shake_handle.absorb(b"samplers");
+        shake_handle.finalize_xof()
+    };
+    let (mut xof1, mut xof2) = (xof(), xof());
+    let mut a = Poly::new();
+    let mut b = Poly::new();
+
+    Poly::uniform_preinit(&mut a, &mut xof1);
+    Poly::uniform_preinit(&mut b, &mut xof2);
+    assert_eq!(a.coeffs, b.coeffs);
+    assert!(a.coeffs.iter().all(|&x| (0..Q).contains(&x)));
+
+    Poly::trinary_preinit(&mut a, &mut xof1);
+    Poly::trinary_preinit(&mut b, &mut xof2);
+    assert_eq!(a.coeffs, b.coeffs);
+    assert!(a.coeffs.iter().all(|&x| (-1..=1).contains(&x)));
+
+    Poly::cbd_preinit(&mut a, &mut xof1, 2);
+    Poly::cbd_preinit(&mut b, &mut xof2, 2);
+    assert_eq!(a.coeffs, b.coeffs);
+    assert!(a.coeffs.iter().all(|&x| (-2..=2).contains(&x)));
+
+    Poly::gaussian_preinit(&mut a, &mut xof1, 3.2);
+    Poly::gaussian_preinit(&mut b, &mut xof2, 3.2);
+    assert_eq!(a.coeffs, b.coeffs);
+
+    Poly::uniform_gamma_preinit(&mut a, &mut xof1);
+    Poly::uniform_gamma_preinit(&mut b, &mut xof2);
+    assert_eq!(a.coeffs, b.coeffs);
+    assert!(a.coeffs.iter().all(|&x| x.unsigned_abs() < GAMMA1 as u32));
+}
+
+#[test]
+fn test_transcript_challenges() {
+    let mut t1 = Transcript::new(b"test");
+    t1.append(b"msg", &[1u8; 40]);
+    let mut t2 = t1.clone();
+    let mut xof1 = t1.challenge_xof(b"c");
+    let mut xof2 = t2.challenge_xof(b"c");
+    assert_eq!(xof1.read_u64_le(), xof2.read_u64_le());
+
+    let mut xof3 = t1.challenge_xof(b"c");
+    let mut xof4 = t2.challenge_xof(b"d");
+    assert_ne!(xof3.read_u64_le(), xof4.read_u64_le());
+}
+
+#[test]
+fn test_fixed_weight_challenge() {
+    let mut t = Transcript::new(b"test");
+    let mut xof = t.challenge_xof(b"c");
+    let c = transcript::sample_ternary_challenge(&mut xof, Some(20));
+    for p in c.iter() {
+        let mut weight = 0;
+        for i in 0..N {
+            let x = p.coeffs[i];
+            assert!(x == 0 || x == 1 || x == -1, "Failing at index {}", i);
+            if x != 0 {
+                assert_eq!(i % R, 0, "Failing at index {}", i);
+                weight += 1;
+            }
+        }
+        assert_eq!(weight, 20);
+    }
+}
+
+#[test]
+fn test_transcript_canonical_polys() {
+    let mut a = Poly::new();
+    for i in 0..N {
+        a.coeffs[i] = (i as i32 * 8380417) % Q;
+    }
+    let mut b = a;
+    for i in 0..N {
+        b.coeffs[i] += if i % 2 == 0 { Q } else { -Q };
+    }
+    assert_eq!(transcript::encode_polys([&a]), transcript::encode_polys([&b]));
+    assert_eq!(transcript::encode_polys([&a]).len(), N*30/8);
+
+    let (mut t1, mut t2) = (Transcript::new(b"test"), Transcript::new(b"test"));
+    t1.append_poly(b"a", &a);
+    t2.append_poly(b"a", &b);
+    assert_eq!(t1.challenge_xof(b"c").read_u64_le(), t2.challenge_xof(b"c").read_u64_le());
+}
+
+#[test]
+fn test_sample_challenge() {
+    let mut t = Transcript::new(b"test");
+    let c = transcript::sample_challenge(&mut t.challenge_xof(b"c"));
+    let c2 = transcript::sample_challenge(&mut t.clone().challenge_xof(b"c"));
+    assert_ne!(c[0].coeffs, c2[0].coeffs);
+    for p in c.iter() {
+        assert!(p.coeffs.iter().enumerate().all(|(i, &x)| x == 0 || (x.abs() == 1 && i % R == 0)));
+        if let ChallengeDist::FixedWeight(tau) = CHALLENGE_DIST {
+            assert_eq!(p.coeffs.iter().filter(|&&x| x != 0).count(), tau);
+        }
+    }
+}
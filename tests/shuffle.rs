@@ -0,0 +1,71 @@
+use irelzk_rs::{
+    comm::commitment::{Comm, CommKey, CommRnd},
+    equality::Witness,
+    params::{M, N, Q, SYMBYTES},
+    poly_arith::polyvec::PolyVecM,
+    shuffle::ShuffleProof
+};
+
+fn permuted() -> (PolyVecM, PolyVecM) {
+    let mut a = PolyVecM::new();
+    let mut b = PolyVecM::new();
+    for i in 0..N {
+        a.vec[0].coeffs[i] = (i as i32 * 7919 + 3) % Q;
+        b.vec[2].coeffs[(i * 37 + 5) % N] = a.vec[0].coeffs[i];
+    }
+    (a, b)
+}
+
+#[test]
+fn test_shuffle_proof() {
+    let ck1 = CommKey::expand(&[41u8; SYMBYTES]);
+    let ck2 = CommKey::expand(&[42u8; SYMBYTES]);
+    let (m1, m2) = permuted();
+    let (r1, r2) = (CommRnd::generate(), CommRnd::generate());
+    let t1 = Comm::commit(&ck1, &r1, &m1);
+    let t2 = Comm::commit(&ck2, &r2, &m2);
+
+    let p = ShuffleProof::prove(
+        &Witness { ck: &ck1, t: &t1, r: &r1, msg: &m1, slot: 0 },
+        &Witness { ck: &ck2, t: &t2, r: &r2, msg: &m2, slot: 2 },
+    ).expect("a challenge hit a message slot");
+    let (tc1, tc2) = (t1.compress(), t2.compress());
+    assert!(!p.verify(&ck1, &tc1, 0, &ck2, &tc2, 2));
+    assert!(p.verify(&ck1, &tc1, 0, &ck2, &tc2, 1));
+    assert!(p.verify(&ck1, &tc1, M, &ck2, &tc2, 2));
+    assert!(p.verify(&ck2, &tc1, 0, &ck2, &tc2, 2));
+
+    let mut tc2 = tc2;
+    tc2.tm.vec[2].coeffs[0] = (tc2.tm.vec[2].coeffs[0] + 1) % Q;
+    assert!(p.verify(&ck1, &tc1, 0, &ck2, &tc2, 2));
+}
+
+#[test]
+#[should_panic]
+fn test_shuffle_proof_not_permutation() {
+    let ck = CommKey::expand(&[43u8; SYMBYTES]);
+    let (m1, mut m2) = permuted();
+    m2.vec[2].coeffs[0] = (m2.vec[2].coeffs[0] + 1) % Q;
+    let (r1, r2) = (CommRnd::generate(), CommRnd::generate());
+    let t1 = Comm::commit(&ck, &r1, &m1);
+    let t2 = Comm::commit(&ck, &r2, &m2);
+    ShuffleProof::prove(
+        &Witness { ck: &ck, t: &t1, r: &r1, msg: &m1, slot: 0 },
+        &Witness { ck: &ck, t: &t2, r: &r2, msg: &m2, slot: 2 },
+    );
+}
+
+#[test]
+fn test_shuffle_verify_rejects_non_permutation() {
+    let ck = CommKey::expand(&[44u8; SYMBYTES]);
+    let (m1, mut m2) = permuted();
+    m2.vec[2].coeffs[0] = (m2.vec[2].coeffs[0] + 1) % Q;
+    let (r1, r2) = (CommRnd::generate(), CommRnd::generate());
+    let t1 = Comm::commit(&ck, &r1, &m1);
+    let t2 = Comm::commit(&ck, &r2, &m2);
+    let p = ShuffleProof::prove_unchecked(
+        &Witness { ck: &ck, t: &t1, r: &r1, msg: &m1, slot: 0 },
+        &Witness { ck: &ck, t: &t2, r: &r2, msg: &m2, slot: 2 },
+    ).expect("a challenge hit a message slot");
+    assert!(p.verify(&ck, &t1.compress(), 0, &ck, &t2.compress(), 2));
+}
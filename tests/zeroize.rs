@@ -0,0 +1,22 @@
+use irelzk_rs::{comm::commitment::CommRnd, crypto::keccak::KeccakState, params::N};
+use zeroize::Zeroize;
+
+#[test]
+fn test_commrnd_zeroize() {
+    let mut r = CommRnd::generate();
+    r.zeroize();
+    for p in r.s.vec.iter().chain(r.e.vec.iter()).chain(r.em.vec.iter()) {
+        for i in 0..N {
+            assert_eq!(p.coeffs[i], 0, "Failing at index {}", i);
+        }
+    }
+}
+
+#[test]
+fn test_keccak_zeroize() {
+    let mut state = KeccakState::init();
+    state.pos = KeccakState::absorb(&mut state.s, 168, state.pos, &[0xA5u8; 200]);
+    state.zeroize();
+    assert_eq!(state.s, [0u64; 25]);
+    assert_eq!(state.pos, 0);
+}